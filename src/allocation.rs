@@ -1,16 +1,19 @@
-use std::error::Error;
-use std::collections::HashMap;
+use std::collections::{BTreeSet, HashMap};
 use std::fmt;
+use std::str::FromStr;
 
 use colored::Colorize;
+use serde::{Serialize, Serializer};
+use serde::de::{self, Deserialize, Deserializer, Unexpected, Visitor};
 use strum::{Display, IntoStaticStr};
 
-use crate::consts::{SEMESTER_KEY_RE, TWELVE_HOUR_TIME_RE};
-use crate::error::{ParseError, TableError};
+use crate::consts::{DURATION_RE, SEMESTER_KEY_RE, TWELVE_HOUR_TIME_RE};
+use crate::error::{self, ParseError, TableError};
+use crate::session::Session;
 
-pub type AllocationResult = Result<Option<Allocation>, Box<dyn Error>>;
+pub type AllocationResult = error::Result<Vec<Allocation>>;
 
-#[derive(Debug)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
 pub struct TwentyFourHourTime {
     pub hours: u8,
     pub minutes: u8,
@@ -53,20 +56,62 @@ impl TwentyFourHourTime {
             None => return None,
         };
 
-        if period.to_ascii_lowercase() == "pm" {
+        if period.eq_ignore_ascii_case("pm") {
             hours += 12;
         }
 
         Some(TwentyFourHourTime { hours, minutes })
     }
 
+    pub fn minutes_since_midnight(&self) -> u32 {
+        self.hours as u32 * 60 + self.minutes as u32
+    }
+
+    /// The time `minutes` after midnight, or `None` if that is not on the same day.
+    pub fn from_minutes_since_midnight(minutes: u32) -> Option<Self> {
+        (minutes < 24 * 60).then_some(Self { hours: (minutes / 60) as u8, minutes: (minutes % 60) as u8 })
+    }
+
     pub fn progress_one_hour(&self) -> Self {
         Self { hours: self.hours + 1, minutes: self.minutes }
     }
+
+    /// Like [`progress_one_hour`](Self::progress_one_hour), but `None` when the result
+    /// would no longer be a valid time of day.
+    pub fn checked_progress_one_hour(&self) -> Option<Self> {
+        (self.hours < 23).then(|| self.progress_one_hour())
+    }
+}
+
+impl<'de> Deserialize<'de> for TwentyFourHourTime {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct TimeVisitor;
+
+        impl<'de> Visitor<'de> for TimeVisitor {
+            type Value = TwentyFourHourTime;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a time such as \"13:30\" or \"1:30pm\"")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                TwentyFourHourTime::new(value).ok_or_else(|| E::invalid_value(Unexpected::Str(value), &self))
+            }
+        }
+
+        deserializer.deserialize_str(TimeVisitor)
+    }
+}
+
+impl Serialize for TwentyFourHourTime {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
 }
 
-#[derive(Debug, Display, PartialEq, Clone)]
+#[derive(Debug, Display, PartialEq, Clone, Default)]
 pub enum Semester {
+    #[default]
     Any = 0,
     One = 1,
     Two = 2,
@@ -106,7 +151,40 @@ impl TryFrom<String> for Semester {
     }
 }
 
-#[derive(IntoStaticStr, Debug, Clone, Copy)]
+impl<'de> Deserialize<'de> for Semester {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct SemesterVisitor;
+
+        impl<'de> Visitor<'de> for SemesterVisitor {
+            type Value = Semester;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("a semester (0, 1 or 2)")
+            }
+
+            fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+                Semester::try_from(value).map_err(|_| E::invalid_value(Unexpected::Unsigned(value), &self))
+            }
+
+            fn visit_i64<E: de::Error>(self, value: i64) -> Result<Self::Value, E> {
+                match u64::try_from(value) {
+                    Ok(value) => self.visit_u64(value),
+                    Err(_) => Err(E::invalid_value(Unexpected::Signed(value), &self)),
+                }
+            }
+        }
+
+        deserializer.deserialize_u64(SemesterVisitor)
+    }
+}
+
+impl Serialize for Semester {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_u8(self.clone() as u8)
+    }
+}
+
+#[derive(IntoStaticStr, Debug, Clone, Copy, PartialEq)]
 pub enum ActivityType {
     Assesment,
     CompulsoryLecture,
@@ -162,7 +240,33 @@ impl ActivityType {
     }
 }
 
-#[derive(Debug, Clone, Copy)]
+impl<'de> Deserialize<'de> for ActivityType {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ActivityTypeVisitor;
+
+        impl<'de> Visitor<'de> for ActivityTypeVisitor {
+            type Value = ActivityType;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("an activity type listed on the public timetable, such as \"Tutorial\"")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                ActivityType::try_from(value).map_err(|_| E::invalid_value(Unexpected::Str(value), &self))
+            }
+        }
+
+        deserializer.deserialize_str(ActivityTypeVisitor)
+    }
+}
+
+impl Serialize for ActivityType {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.checkbox_id_suffix())
+    }
+}
+
+#[derive(IntoStaticStr, Debug, Clone, Copy, PartialEq)]
 pub enum Day {
     Monday = 1,
     Tuesday = 2,
@@ -173,6 +277,17 @@ pub enum Day {
     Sunday = 7,
 }
 
+/// Every day of the week, starting on Monday.
+pub const DAYS: [Day; 7] = [
+    Day::Monday, 
+    Day::Tuesday, 
+    Day::Wednesday, 
+    Day::Thursday, 
+    Day::Friday, 
+    Day::Saturday, 
+    Day::Sunday
+];
+
 impl TryFrom<u64> for Day {
     type Error = ParseError;
 
@@ -207,8 +322,244 @@ impl TryFrom<&str> for Day {
     }
 }
 
+impl<'de> Deserialize<'de> for Day {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct DayVisitor;
+
+        impl<'de> Visitor<'de> for DayVisitor {
+            type Value = Day;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("an ISO weekday (1-7) or the (abbreviated) name of a weekday")
+            }
+
+            fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+                Day::try_from(value).map_err(|_| E::invalid_value(Unexpected::Unsigned(value), &self))
+            }
+
+            fn visit_i64<E: de::Error>(self, value: i64) -> Result<Self::Value, E> {
+                match u64::try_from(value) {
+                    Ok(value) => self.visit_u64(value),
+                    Err(_) => Err(E::invalid_value(Unexpected::Signed(value), &self)),
+                }
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                Day::try_from(value).map_err(|_| E::invalid_value(Unexpected::Str(value), &self))
+            }
+        }
+
+        deserializer.deserialize_any(DayVisitor)
+    }
+}
+
+impl Serialize for Day {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(self.into())
+    }
+}
+
+/// A set of teaching weeks, written as weeks and ranges of weeks such as "1-6, 8-13".
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct WeekSet(BTreeSet<u8>);
+
+impl WeekSet {
+    pub fn contains(&self, week: u8) -> bool {
+        self.0.contains(&week)
+    }
+
+    pub fn is_subset(&self, other: &WeekSet) -> bool {
+        self.0.is_subset(&other.0)
+    }
+
+    pub fn intersects(&self, other: &WeekSet) -> bool {
+        !self.0.is_disjoint(&other.0)
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
+    }
+
+    pub fn iter(&self) -> impl Iterator<Item = u8> + '_ {
+        self.0.iter().copied()
+    }
+}
+
+impl FromStr for WeekSet {
+    type Err = ParseError;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let invalid = || ParseError::ParseWeeksError(value.to_owned());
+        let parse = |week: &str| week.trim().parse::<u8>().map_err(|_| invalid());
+        let mut weeks = BTreeSet::new();
+
+        for part in value.split(',').filter(|part| !part.trim().is_empty()) {
+            match part.split_once('-') {
+                Some((from, to)) => {
+                    let (from, to) = (parse(from)?, parse(to)?);
+                    if from > to {
+                        return Err(invalid());
+                    }
+                    weeks.extend(from..=to);
+                }
+                None => {
+                    weeks.insert(parse(part)?);
+                }
+            }
+        }
+
+        if weeks.is_empty() {
+            return Err(invalid());
+        }
+        Ok(Self(weeks))
+    }
+}
+
+impl fmt::Display for WeekSet {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut ranges: Vec<(u8, u8)> = Vec::new();
+        for week in self.iter() {
+            match ranges.last_mut() {
+                Some((_, to)) if *to + 1 == week => *to = week,
+                _ => ranges.push((week, week)),
+            }
+        }
+
+        let ranges: Vec<String> = ranges
+            .into_iter()
+            .map(|(from, to)| if from == to { from.to_string() } else { format!("{}-{}", from, to) })
+            .collect();
+        write!(f, "{}", ranges.join(", "))
+    }
+}
+
+impl<'de> Deserialize<'de> for WeekSet {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct WeekSetVisitor;
+
+        impl<'de> Visitor<'de> for WeekSetVisitor {
+            type Value = WeekSet;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("teaching weeks such as \"1-6, 8-13\"")
+            }
+
+            fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+                match u8::try_from(value) {
+                    Ok(week) => Ok(WeekSet(BTreeSet::from([week]))),
+                    Err(_) => Err(E::invalid_value(Unexpected::Unsigned(value), &self)),
+                }
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                value.parse().map_err(|_| E::invalid_value(Unexpected::Str(value), &self))
+            }
+        }
+
+        deserializer.deserialize_any(WeekSetVisitor)
+    }
+}
+
+impl Serialize for WeekSet {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.collect_str(self)
+    }
+}
+
+/// The length of an activity as shown on the timetable, e.g. "1 hr 30 mins".
+#[derive(Debug, Clone, PartialEq)]
+pub struct ActivityDuration {
+    raw: String,
+    minutes: Option<u32>,
+}
+
+impl ActivityDuration {
+    pub fn new(raw: impl Into<String>) -> Self {
+        let raw = raw.into();
+        let minutes = parse_duration_minutes(&raw);
+        Self { raw, minutes }
+    }
+
+    /// The number of minutes, if the duration could be understood.
+    pub fn minutes(&self) -> Option<u32> {
+        self.minutes
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.raw
+    }
+}
+
+impl fmt::Display for ActivityDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.raw)
+    }
+}
+
+impl Serialize for ActivityDuration {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.raw)
+    }
+}
+
+/// The number of minutes in a duration such as "1 hr", "1 hr 30 mins" or "1.5 hours".
+/// Anything else, including a number without a unit, is not understood.
+fn parse_duration_minutes(value: &str) -> Option<u32> {
+    let mut minutes = 0.0;
+    let mut rest = value.trim();
+    if rest.is_empty() {
+        return None;
+    }
+
+    while !rest.is_empty() {
+        let caps = DURATION_RE.captures(rest)?;
+        let amount = caps[1].parse::<f64>().ok()?;
+        let unit = if caps[2].to_ascii_lowercase().starts_with('h') { 60.0 } else { 1.0 };
+        minutes += amount * unit;
+        rest = rest[caps[0].len()..].trim_start();
+    }
+
+    (minutes > 0.0).then_some(minutes.round() as u32)
+}
+
+/// The teaching weeks an activity runs in as shown on the timetable, e.g. "1-6, 8-13".
+#[derive(Debug, Clone, PartialEq)]
+pub struct TeachingWeeks {
+    raw: String,
+    weeks: Option<WeekSet>,
+}
+
+impl TeachingWeeks {
+    pub fn new(raw: impl Into<String>) -> Self {
+        let raw = raw.into();
+        let weeks = raw.parse().ok();
+        Self { raw, weeks }
+    }
+
+    /// The weeks, if they could be understood.
+    pub fn weeks(&self) -> Option<&WeekSet> {
+        self.weeks.as_ref()
+    }
+
+    pub fn as_str(&self) -> &str {
+        &self.raw
+    }
+}
+
+impl fmt::Display for TeachingWeeks {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.raw)
+    }
+}
+
+impl Serialize for TeachingWeeks {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.raw)
+    }
+}
+
 #[allow(dead_code)]
-#[derive(Debug)]
+#[derive(Debug, Clone, Serialize)]
 pub struct Allocation {
     pub activity_type: ActivityType,
     pub group: String,
@@ -218,12 +569,12 @@ pub struct Allocation {
     pub day: Day,
     pub time: TwentyFourHourTime,
 
-    pub semester: Semester,
+    pub semester: Session,
     pub campus: String,
     pub location: String,
 
-    pub duration: String,
-    pub weeks: String,
+    pub duration: ActivityDuration,
+    pub weeks: TeachingWeeks,
     pub seats: i16,
 }
 
@@ -231,14 +582,18 @@ fn allocation_table_get(map: &HashMap<String, String>, key: &str) -> Result<Stri
     map.get(key).ok_or(TableError::RowMissingError(key.to_owned())).cloned()
 }
 
+fn parse_number<T: std::str::FromStr>(value: String) -> Result<T, ParseError> {
+    value.parse::<T>().map_err(|_| ParseError::ParseNumberError(value))
+}
+
 impl Allocation {
-    pub fn try_new(table: &HashMap<String, String>) -> Result<Allocation, Box<dyn Error>> {
+    pub fn try_new(table: &HashMap<String, String>) -> error::Result<Allocation> {
         let activity_type = ActivityType::try_from(
             allocation_table_get(table, "Activity Type")?.as_str()
         )?;
 
         let group = allocation_table_get(table, "Group")?;
-        let activity = allocation_table_get(table, "Activity")?.parse::<u64>()?;
+        let activity = parse_number(allocation_table_get(table, "Activity")?)?;
         let description = allocation_table_get(table, "Description")?;
 
         let day = Day::try_from(
@@ -248,15 +603,13 @@ impl Allocation {
         let time = TwentyFourHourTime::new(&time_string)
             .ok_or(ParseError::ParseTimeError(time_string))?;
 
-        let semester = Semester::try_from(
-            allocation_table_get(table, "Semester")?
-        )?;
+        let semester = Session::from_label(&allocation_table_get(table, "Semester")?);
         let campus = allocation_table_get(table, "Campus")?;
         let location = allocation_table_get(table, "Location")?;
 
-        let duration = allocation_table_get(table, "Duration")?;
-        let weeks = allocation_table_get(table, "Weeks")?;
-        let seats = allocation_table_get(table, "Seats")?.parse::<i16>()?;
+        let duration = ActivityDuration::new(allocation_table_get(table, "Duration")?);
+        let weeks = TeachingWeeks::new(allocation_table_get(table, "Weeks")?);
+        let seats = parse_number(allocation_table_get(table, "Seats")?)?;
 
         Ok(Allocation {
             activity_type,
@@ -274,6 +627,19 @@ impl Allocation {
         })
     }
 
+    /// The time the activity ends, if its duration could be understood and
+    /// it ends on the same day.
+    pub fn end_time(&self) -> Option<TwentyFourHourTime> {
+        let end = self.time.minutes_since_midnight() + self.duration.minutes()?;
+        TwentyFourHourTime::from_minutes_since_midnight(end)
+    }
+
+    /// Whether the activity runs in teaching week `week`, or `None` if its weeks
+    /// could not be understood.
+    pub fn runs_in_week(&self, week: u8) -> Option<bool> {
+        self.weeks.weeks().map(|weeks| weeks.contains(week))
+    }
+
     pub fn notify_query_resolved(&self, unit_code: String) {
         let resolved = format!("Activity {} of {} has {} seats left", self.activity, unit_code, self.seats);
         println!("{}", resolved.green());
@@ -0,0 +1,171 @@
+use std::fs;
+use std::path::PathBuf;
+
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, Utc};
+use log::warn;
+use serde::Deserialize;
+
+use crate::allocation::Allocation;
+use crate::consts::{DEFAULT_CALENDAR_FILE, ICS_LINE_LENGTH};
+use crate::error::Result;
+
+/// Where found allocations are exported to as an iCalendar file.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct CalendarConfig {
+    #[serde(default = "default_calendar_path")]
+    pub path: PathBuf,
+    /// A date in the first teaching week of the semester
+    pub semester_start: NaiveDate,
+    /// The teaching week after which the mid-semester break is
+    #[serde(default)]
+    pub mid_semester_break: Option<u8>,
+}
+
+fn default_calendar_path() -> PathBuf {
+    PathBuf::from(DEFAULT_CALENDAR_FILE)
+}
+
+impl CalendarConfig {
+    /// The Monday of a teaching week.
+    pub fn week_start(&self, week: u8) -> NaiveDate {
+        let first = self.semester_start - Duration::days(self.semester_start.weekday().num_days_from_monday() as i64);
+        let breaks = self.mid_semester_break.is_some_and(|after| week > after) as i64;
+        first + Duration::weeks(week.saturating_sub(1) as i64 + breaks)
+    }
+}
+
+/// An allocation along with the unit it belongs to.
+#[derive(Debug, Clone)]
+pub struct CalendarEntry {
+    pub unit_code: String,
+    pub allocation: Allocation,
+}
+
+/// Renders allocations as an iCalendar file with one weekly recurring event per allocation.
+#[derive(Debug)]
+pub struct Calendar<'a> {
+    config: &'a CalendarConfig,
+}
+
+impl<'a> Calendar<'a> {
+    pub fn new(config: &'a CalendarConfig) -> Self {
+        Self { config }
+    }
+
+    /// Writes the allocations to the configured path, returning how many events were written.
+    /// Allocations whose duration or weeks cannot be understood are left out.
+    pub fn write(&self, entries: &[CalendarEntry]) -> Result<usize> {
+        let events: Vec<String> = entries.iter().filter_map(|entry| self.event(entry)).collect();
+        let count = events.len();
+
+        let mut lines = vec![
+            "BEGIN:VCALENDAR".to_owned(),
+            "VERSION:2.0".to_owned(),
+            format!("PRODID:-//seatfinder//seatfinder {}//EN", env!("CARGO_PKG_VERSION")),
+            "CALSCALE:GREGORIAN".to_owned(),
+        ];
+        lines.extend(events);
+        lines.push("END:VCALENDAR".to_owned());
+
+        fs::write(&self.config.path, lines.join("\r\n") + "\r\n")?;
+        Ok(count)
+    }
+
+    /// The VEVENT of an allocation, with its lines joined and folded.
+    pub fn event(&self, CalendarEntry { unit_code, allocation }: &CalendarEntry) -> Option<String> {
+        let (Some(end_time), Some(weeks)) = (allocation.end_time(), allocation.weeks.weeks()) else {
+            warn!(
+                "Not exporting activity {} of {}: its duration {:?} or weeks {:?} could not be understood",
+                allocation.activity,
+                unit_code,
+                allocation.duration.as_str(),
+                allocation.weeks.as_str()
+            );
+            return None;
+        };
+
+        let day_offset = Duration::days(allocation.day as i64 - 1);
+        let time = allocation.time;
+        let occurrence = |week: u8| -> NaiveDateTime {
+            let date = self.config.week_start(week) + day_offset;
+            date.and_hms_opt(time.hours as u32, time.minutes as u32, 0).unwrap_or_default()
+        };
+
+        let occurrences: Vec<NaiveDateTime> = weeks.iter().map(occurrence).collect();
+        let (&start, &until) = (occurrences.first()?, occurrences.last()?);
+        // Every week between the first and last occurrence is excluded unless it is one,
+        // e.g. weeks the activity skips and the mid-semester break
+        let excluded: Vec<String> = (0..)
+            .map(|weeks| start + Duration::weeks(weeks))
+            .take_while(|&datetime| datetime < until)
+            .filter(|datetime| !occurrences.contains(datetime))
+            .map(ics_datetime)
+            .collect();
+        let end = start.date().and_hms_opt(end_time.hours as u32, end_time.minutes as u32, 0)?;
+
+        let activity_type = allocation.activity_type.checkbox_id_suffix();
+        let description = format!(
+            "{}\nGroup: {}\nWeeks: {}\nCampus: {}",
+            allocation.description,
+            allocation.group,
+            allocation.weeks,
+            allocation.campus
+        );
+
+        let mut lines = vec![
+            "BEGIN:VEVENT".to_owned(),
+            format!(
+                "UID:{}-{}-{}-{}@seatfinder",
+                unit_code,
+                activity_type.replace(' ', ""),
+                allocation.activity,
+                self.config.semester_start.format("%Y%m%d")
+            ),
+            format!("DTSTAMP:{}", Utc::now().format("%Y%m%dT%H%M%SZ")),
+            format!("DTSTART:{}", ics_datetime(start)),
+            format!("DTEND:{}", ics_datetime(end)),
+            format!("RRULE:FREQ=WEEKLY;UNTIL={}", ics_datetime(until)),
+        ];
+        if !excluded.is_empty() {
+            lines.push(format!("EXDATE:{}", excluded.join(",")));
+        }
+        lines.extend([
+            format!("SUMMARY:{}", escape(&format!("{} {} {}", unit_code, activity_type, allocation.activity))),
+            format!("LOCATION:{}", escape(&allocation.location)),
+            format!("DESCRIPTION:{}", escape(&description)),
+            "END:VEVENT".to_owned(),
+        ]);
+
+        let folded: Vec<String> = lines.iter().map(|line| fold(line)).collect();
+        Some(folded.join("\r\n"))
+    }
+}
+
+/// A local date and time without a time zone, which calendars show as is.
+fn ics_datetime(datetime: NaiveDateTime) -> String {
+    datetime.format("%Y%m%dT%H%M%S").to_string()
+}
+
+fn escape(text: &str) -> String {
+    text.replace('\\', "\\\\")
+        .replace(';', "\\;")
+        .replace(',', "\\,")
+        .replace('\n', "\\n")
+}
+
+/// Splits a line into lines of at most 75 bytes, continuing each with a space.
+fn fold(line: &str) -> String {
+    let mut folded = String::with_capacity(line.len());
+    let mut length = 0;
+
+    for c in line.chars() {
+        if length + c.len_utf8() > ICS_LINE_LENGTH {
+            folded.push_str("\r\n ");
+            length = 1;
+        }
+        folded.push(c);
+        length += c.len_utf8();
+    }
+    folded
+}
@@ -0,0 +1,212 @@
+use std::fmt;
+use std::path::PathBuf;
+
+use colored::Colorize;
+
+use crate::allocation::TwentyFourHourTime;
+use crate::consts::LATEST_START_AFTER;
+use crate::session::Session;
+use crate::config::Config;
+use crate::notifier::NotifierConfig;
+
+#[derive(Debug)]
+pub enum ConfigWarning {
+    AmbiguousSemester { query: usize, unit_code: String },
+    StartAfterOverflow { query: usize, start_after: TwentyFourHourTime },
+    EmptyTimeRange { query: usize, start_after: TwentyFourHourTime, start_before: TwentyFourHourTime },
+    MissingAudio { notifier: String, path: PathBuf },
+    EmptyCommitment { name: String },
+}
+
+impl fmt::Display for ConfigWarning {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::AmbiguousSemester { query, unit_code } => write!(
+                f,
+                "query {}: no semester or session (or semester 0) is given, so the first offering of {} is chosen",
+                query,
+                unit_code
+            ),
+            Self::StartAfterOverflow { query, start_after } => write!(
+                f,
+                "query {}: start_after {} is later than 22:00, so the timetable cannot filter by it when it is entered one hour later",
+                query,
+                start_after
+            ),
+            Self::EmptyTimeRange { query, start_after, start_before } => write!(
+                f,
+                "query {}: no activity can start after {} and before {}",
+                query,
+                start_after,
+                start_before
+            ),
+            Self::MissingAudio { notifier, path } => write!(
+                f,
+                "notifier {:?}: audio file {} does not exist",
+                notifier,
+                path.display()
+            ),
+            Self::EmptyCommitment { name } => write!(
+                f,
+                "commitment {:?} does not end after it starts, so nothing can clash with it",
+                name
+            ),
+        }
+    }
+}
+
+/// A description of a parsed config that is printed by `check-config`.
+pub struct ConfigReport<'a> {
+    config: &'a Config,
+    pub warnings: Vec<ConfigWarning>,
+}
+
+impl<'a> ConfigReport<'a> {
+    pub fn new(config: &'a Config) -> Self {
+        let mut warnings = Vec::new();
+
+        for (index, query) in config.queries.iter().enumerate() {
+            if query.session == Session::Any && query.subcode.is_none() {
+                warnings.push(ConfigWarning::AmbiguousSemester {
+                    query: index + 1,
+                    unit_code: query.unit_code()
+                });
+            }
+
+            if let Some(start_after) = query.start_after {
+                if start_after.minutes_since_midnight() > LATEST_START_AFTER {
+                    warnings.push(ConfigWarning::StartAfterOverflow { query: index + 1, start_after });
+                }
+            }
+
+            if let (Some(start_after), Some(start_before)) = (query.start_after, query.start_before) {
+                if start_after > start_before {
+                    warnings.push(ConfigWarning::EmptyTimeRange { query: index + 1, start_after, start_before });
+                }
+            }
+        }
+
+        for (name, notifier) in config.finder.notifiers.iter() {
+            if let NotifierConfig::Audio { ref path, .. } = notifier {
+                if !path.is_file() {
+                    warnings.push(ConfigWarning::MissingAudio { notifier: name.clone(), path: path.clone() });
+                }
+            }
+        }
+
+        for commitment in config.finder.planner.commitments.iter() {
+            if commitment.start >= commitment.end {
+                warnings.push(ConfigWarning::EmptyCommitment { name: commitment.name.clone() });
+            }
+        }
+
+        Self { config, warnings }
+    }
+
+    pub fn print(&self) {
+        let finder = &self.config.finder;
+        println!("timetable:        {}", finder.public_timetable_url);
+        println!("port:             {}", finder.port);
+        println!("headless:         {}", finder.headless);
+        println!("run_chromedriver: {}", finder.run_chromedriver);
+        if finder.notify.is_empty() {
+            println!("notify:           -");
+        } else {
+            println!("notify:           {}", finder.notify.join(", "));
+        }
+        for (name, notifier) in finder.notifiers.iter() {
+            println!("  {}: {}", name, notifier);
+        }
+        println!("cooldown:         {}s", finder.alerts.cooldown);
+        match finder.alerts.threshold {
+            Some(threshold) => println!("threshold:        {} seats", threshold),
+            None => println!("threshold:        -"),
+        }
+        match finder.alerts.reminder {
+            Some(reminder) => println!("reminder:         every {}s", reminder),
+            None => println!("reminder:         -"),
+        }
+        let schedule = &finder.schedule;
+        println!("interval:         {}s", schedule.interval);
+        for quiet_hours in schedule.quiet_hours.iter() {
+            println!("  quiet:          {}-{}", quiet_hours.start, quiet_hours.end);
+        }
+        for window in schedule.windows.iter() {
+            println!("  window:         {}-{} every {}s", window.start, window.end, window.interval);
+        }
+        println!("suppress_clashes: {}", finder.planner.suppress_clashes);
+        for commitment in finder.planner.commitments.iter() {
+            match commitment.weeks {
+                Some(ref weeks) => println!("  {}: {} (weeks {})", commitment.name, commitment.slot(), weeks),
+                None => println!("  {}: {}", commitment.name, commitment.slot()),
+            }
+        }
+        match finder.calendar {
+            Some(ref calendar) => println!(
+                "calendar:         {} (semester starts {})", 
+                calendar.path.display(), 
+                calendar.semester_start
+            ),
+            None => println!("calendar:         -"),
+        }
+        if finder.history.enabled {
+            println!("history:          {}", finder.history.path.display());
+        } else {
+            println!("history:          -");
+        }
+
+        for (index, query) in self.config.queries.iter().enumerate() {
+            let heading = format!("query {}", index + 1);
+            println!();
+            println!("{}", heading.bold());
+            println!("  unit_code:      {}", query.unit_code);
+            if let Some(ref alternative) = query.alternative {
+                println!("  group:          {} ({} of {})", alternative.group, alternative.rank, alternative.of);
+            }
+            let days: Vec<&str> = query.days.iter().map(|&day| day.into()).collect();
+            println!("  day:            {}", days.join(", "));
+            match query.subcode {
+                Some(ref subcode) => println!("  subcode:        {}", subcode),
+                None => println!("  semester:       {}", query.session),
+            }
+            println!("  activity_type:  {}", query.activity_type.checkbox_id_suffix());
+            println!("  activity:       {}", query.activity);
+            match query.start_after {
+                Some(start_after) => println!("  start_after:    {}", start_after),
+                None => println!("  start_after:    -"),
+            }
+            if let Some(start_before) = query.start_before {
+                println!("  start_before:   {}", start_before);
+            }
+            if let Some(end_before) = query.end_before {
+                println!("  end_before:     {}", end_before);
+            }
+            if let Some(ref campus) = query.campus {
+                println!("  campus:         {}", campus);
+            }
+            if let Some(ref location) = query.location {
+                println!("  location:       {}", location);
+            }
+            if let Some(min_seats) = query.min_seats {
+                println!("  min_seats:      {}", min_seats);
+            }
+            if let Some(ref weeks) = query.weeks {
+                println!("  weeks:          {}", weeks);
+            }
+            if let Some(interval) = query.interval {
+                println!("  interval:       {}s", interval);
+            }
+            if let Some(ref notify) = query.notify {
+                println!("  notify:         {}", notify.join(", "));
+            }
+        }
+
+        if !self.warnings.is_empty() {
+            println!();
+        }
+        for warning in self.warnings.iter() {
+            let warning = format!("warning: {}", warning);
+            println!("{}", warning.yellow());
+        }
+    }
+}
@@ -0,0 +1,149 @@
+use std::path::PathBuf;
+use std::process;
+
+use clap::{Args, Parser, Subcommand};
+use colored::Colorize;
+
+use crate::allocation::ActivityType;
+use crate::offering::Offering;
+use crate::session::Session;
+use crate::consts::{CONFIG_FILE, MIN_PORT};
+use crate::listing::{ListOptions, ListSort};
+use crate::query::{ConfigOverrides, Parity};
+use crate::report::{ReportFormat, ReportOptions};
+use crate::seatfinder;
+
+#[derive(Parser, Debug)]
+#[command(name = "seatfinder", version, about = "Finds allocations that match queries from a config file")]
+pub struct Cli {
+    #[command(flatten)]
+    pub options: GlobalOptions,
+
+    #[command(subcommand)]
+    pub command: Option<Command>,
+}
+
+#[derive(Args, Debug)]
+pub struct GlobalOptions {
+    /// Path to the config file
+    #[arg(short, long, global = true, default_value = CONFIG_FILE)]
+    pub config: PathBuf,
+
+    /// Run chromedriver in headless mode (overrides `headless`)
+    #[arg(long, global = true, num_args = 0..=1, default_missing_value = "true")]
+    pub headless: Option<bool>,
+
+    /// The port the chromedriver instance is running on (overrides `port`)
+    #[arg(short, long, global = true, value_parser = clap::value_parser!(u16).range(MIN_PORT as i64..))]
+    pub port: Option<u16>,
+
+    /// Which public timetable to use (overrides `parity`)
+    #[arg(long, global = true, value_enum)]
+    pub parity: Option<Parity>,
+}
+
+#[derive(Subcommand, Debug)]
+pub enum Command {
+    /// Poll the timetable until seats are found (the default)
+    Watch {
+        /// Seconds to wait between each search (overrides `schedule.interval`)
+        #[arg(short, long, value_parser = clap::value_parser!(u64).range(1..))]
+        interval: Option<u64>,
+    },
+    /// Search the timetable once and exit
+    Once,
+    /// Validate the config file without starting a browser
+    CheckConfig,
+    /// List the offerings of a unit
+    ListOfferings {
+        /// The code of the unit, e.g. COMP2123
+        unit_code: String,
+        /// Choose one of the offerings and print a query pinned to it
+        #[arg(long)]
+        choose: bool,
+    },
+    /// List every allocation of a unit with its seats
+    List {
+        /// The code of the unit, e.g. COMP2123
+        unit_code: String,
+        /// The session of the offering to list, e.g. 2, S1CIJA or summer (any chooses the first offering)
+        #[arg(short, long, default_value = "any", value_parser = |value: &str| value.parse::<Session>())]
+        session: Session,
+        /// The exact offering to list, e.g. COMP2123-S2C-ND-CC, instead of one in `session`
+        #[arg(long, value_parser = |value: &str| value.parse::<Offering>())]
+        subcode: Option<Offering>,
+        /// Only list activities of this type, e.g. Tutorial
+        #[arg(short = 't', long = "type", value_parser = |value: &str| ActivityType::try_from(value))]
+        activity_type: Option<ActivityType>,
+        /// How to sort the allocations
+        #[arg(long, value_enum, default_value_t = ListSort::Day)]
+        sort: ListSort,
+        /// How to print the allocations
+        #[arg(short, long, value_enum, default_value_t = ReportFormat::Table)]
+        format: ReportFormat,
+    },
+    /// Summarise when seats opened and closed from the recorded history
+    Report {
+        /// How to print the report
+        #[arg(short, long, value_enum, default_value_t = ReportFormat::Table)]
+        format: ReportFormat,
+        /// Only report on this unit, e.g. COMP2123
+        #[arg(short, long)]
+        unit: Option<String>,
+        /// Only use observations from the last this many days
+        #[arg(long)]
+        since: Option<u64>,
+        /// The history file to read instead of the one in the config
+        #[arg(long)]
+        history: Option<PathBuf>,
+    },
+}
+
+impl GlobalOptions {
+    pub fn overrides(&self) -> ConfigOverrides {
+        ConfigOverrides {
+            headless: self.headless,
+            port: self.port,
+            parity: self.parity,
+            interval: None,
+        }
+    }
+}
+
+pub fn run() {
+    let Cli { options, command } = Cli::parse();
+    let mut overrides = options.overrides();
+    let config = options.config.as_path();
+
+    env_logger::init();
+
+    let result = match command.unwrap_or(Command::Watch { interval: None }) {
+        Command::Watch { interval } => {
+            overrides.interval = interval;
+            seatfinder::run_every(config, &overrides)
+        }
+        Command::Once => seatfinder::run(config, &overrides),
+        Command::CheckConfig => seatfinder::check_config(config, &overrides).map_err(|e| {
+            let invalid = format!("{} is invalid: {}", config.display(), e);
+            eprintln!("{}", invalid.red());
+            process::exit(1);
+        }),
+        Command::ListOfferings { unit_code, choose } => {
+            seatfinder::list_offerings(config, &overrides, &unit_code, choose)
+        }
+        Command::List { unit_code, session, subcode, activity_type, sort, format } => {
+            let options = ListOptions { unit_code, session, subcode, activity_type, sort, format };
+            seatfinder::list(config, &overrides, &options)
+        }
+        Command::Report { format, unit, since, history } => {
+            let options = ReportOptions { format, unit_code: unit, since, history };
+            seatfinder::report(config, &overrides, &options)
+        }
+    };
+
+    if let Err(e) = result {
+        let error = format!("error: {}", e);
+        eprintln!("{}", error.red());
+        process::exit(1);
+    }
+}
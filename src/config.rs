@@ -0,0 +1,289 @@
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer};
+use serde::de::{self, Unexpected};
+use serde_json::{self, Value};
+
+use crate::consts::{
+    DEFAULT_AUDIO_REPEAT,
+    DEFAULT_HEADLESS,
+    DEFAULT_PORT,
+    DEFAULT_RUN_CHROMEDRIVER,
+    MIN_PORT,
+    MAX_PORT,
+    MUSIC_NOTIFIER
+};
+use crate::error::{Error, ParseError, Result};
+use crate::notifier::NotifierConfig;
+use crate::query::{ConfigOverrides, FinderConfig, FinderQuery, Parity};
+use crate::retry::RetryConfig;
+use crate::tracker::AlertConfig;
+use crate::history::HistoryConfig;
+use crate::schedule::ScheduleConfig;
+use crate::group::QueryGroup;
+use crate::planner::PlannerConfig;
+use crate::calendar::CalendarConfig;
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum ConfigFormat {
+    Json,
+    Toml,
+    Yaml,
+}
+
+impl ConfigFormat {
+    pub fn from_path(path: &Path) -> Result<Self, ParseError> {
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .unwrap_or_default();
+
+        match extension.to_ascii_lowercase().as_str() {
+            "json" => Ok(Self::Json),
+            "toml" => Ok(Self::Toml),
+            "yaml" | "yml" => Ok(Self::Yaml),
+            _ => Err(ParseError::ParseConfigFormatError(extension.to_owned())),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub struct Config {
+    pub finder: FinderConfig,
+    pub queries: Vec<FinderQuery>,
+}
+
+/// The layout of the config file, before defaults that depend on
+/// the environment (e.g. the parity and port) are resolved.
+#[derive(Debug, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct RawConfig {
+    #[serde(default = "default_headless")]
+    headless: bool,
+    #[serde(default = "default_run_chromedriver")]
+    run_chromedriver: bool,
+    #[serde(default = "default_port", deserialize_with = "deserialize_port")]
+    port: u16,
+    #[serde(default)]
+    parity: Option<Parity>,
+    #[serde(default)]
+    music: Option<PathBuf>,
+    #[serde(default)]
+    retry: RetryConfig,
+    #[serde(default)]
+    notifiers: BTreeMap<String, NotifierConfig>,
+    #[serde(default)]
+    notify: Option<Vec<String>>,
+    #[serde(default)]
+    alerts: AlertConfig,
+    #[serde(default)]
+    history: HistoryConfig,
+    #[serde(default)]
+    schedule: ScheduleConfig,
+    #[serde(default)]
+    planner: PlannerConfig,
+    #[serde(default)]
+    calendar: Option<CalendarConfig>,
+    #[serde(default)]
+    query: Option<FinderQuery>,
+    #[serde(default)]
+    queries: Option<Vec<FinderQuery>>,
+    #[serde(default)]
+    groups: Vec<QueryGroup>,
+}
+
+impl RawConfig {
+    fn apply(&mut self, overrides: &ConfigOverrides) {
+        if let Some(headless) = overrides.headless {
+            self.headless = headless;
+        }
+
+        if let Some(port) = overrides.port {
+            self.port = port;
+        }
+
+        if let Some(parity) = overrides.parity {
+            self.parity = Some(parity);
+        }
+
+        if let Some(interval) = overrides.interval {
+            self.schedule.interval = interval;
+        }
+    }
+
+    fn into_config(mut self) -> Result<Config> {
+        // `query` takes precedence over `queries`
+        let mut queries = match (self.query, self.queries) {
+            (Some(query), _) => vec![query],
+            (None, Some(queries)) => queries,
+            (None, None) if !self.groups.is_empty() => Vec::new(),
+            (None, None) => return Err(ParseError::ParseQueriesError.into()),
+        };
+
+        // The alternatives of groups are searched for like any other query
+        let mut names = Vec::with_capacity(self.groups.len());
+        for group in self.groups {
+            if group.alternatives.is_empty() {
+                return Err(ParseError::ParseEmptyGroupError(group.name).into());
+            }
+            if names.contains(&group.name) {
+                return Err(ParseError::ParseDuplicateGroupError(group.name).into());
+            }
+            names.push(group.name.clone());
+            queries.extend(group.into_queries());
+        }
+
+        for query in queries.iter() {
+            if let Some(ref subcode) = query.subcode {
+                if !subcode.unit_code().eq_ignore_ascii_case(&query.unit_code) {
+                    return Err(ParseError::ParseSubcodeUnitError {
+                        subcode: subcode.to_string(),
+                        unit_code: query.unit_code.clone(),
+                    }.into());
+                }
+            }
+        }
+
+        // `music` is shorthand for an audio notifier named "music"
+        if let Some(path) = self.music {
+            self.notifiers
+                .entry(MUSIC_NOTIFIER.to_owned())
+                .or_insert(NotifierConfig::Audio { path, repeat: DEFAULT_AUDIO_REPEAT });
+        }
+
+        // Without `notify`, every notifier is used
+        let notify = self.notify.unwrap_or_else(|| self.notifiers.keys().cloned().collect());
+        let names = queries
+            .iter()
+            .filter_map(|query| query.notify.as_ref())
+            .flatten()
+            .chain(notify.iter());
+        for name in names {
+            if !self.notifiers.contains_key(name) {
+                return Err(ParseError::ParseNotifierError(name.clone()).into());
+            }
+        }
+
+        let finder = FinderConfig::new(
+            self.port,
+            self.parity,
+            self.headless,
+            self.run_chromedriver,
+            self.retry
+        )?
+        .with_notifiers(self.notifiers, notify)
+        .with_alerts(self.alerts)
+        .with_history(self.history)
+        .with_schedule(self.schedule)
+        .with_planner(self.planner)
+        .with_calendar(self.calendar);
+
+        Ok(Config { finder, queries })
+    }
+}
+
+fn default_headless() -> bool {
+    DEFAULT_HEADLESS
+}
+
+fn default_run_chromedriver() -> bool {
+    DEFAULT_RUN_CHROMEDRIVER
+}
+
+fn default_port() -> u16 {
+    DEFAULT_PORT
+}
+
+fn deserialize_port<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u16, D::Error> {
+    let port = u16::deserialize(deserializer)?;
+    if !(MIN_PORT..=MAX_PORT).contains(&port) {
+        return Err(de::Error::invalid_value(
+            Unexpected::Unsigned(port as u64),
+            &"a port between 1024 and 65535"
+        ));
+    }
+    Ok(port)
+}
+
+fn toml_error(error: serde_path_to_error::Error<toml::de::Error>, contents: &str) -> ParseError {
+    let path = error.path().to_string();
+    let error = error.into_inner();
+
+    // Unlike JSON and YAML, TOML errors only carry a byte span, so the
+    // line and column are recovered from the source
+    let Some(span) = error.span() else {
+        return ParseError::ParseConfigError { 
+            path, 
+            message: error.message().to_owned(), 
+            line: None, 
+            column: None,
+        };
+    };
+    let preceding = &contents[..span.start.min(contents.len())];
+    let line = preceding.matches('\n').count() + 1;
+    let column = preceding.chars().rev().take_while(|&c| c != '\n').count() + 1;
+
+    ParseError::ParseConfigError { 
+        path, 
+        message: format!("{} at line {} column {}", error.message(), line, column), 
+        line: Some(line), 
+        column: Some(column),
+    }
+}
+
+impl Config {
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self> {
+        Self::load(path, &ConfigOverrides::default())
+    }
+
+    pub fn load(path: impl AsRef<Path>, overrides: &ConfigOverrides) -> Result<Self> {
+        let path = path.as_ref();
+        let format = ConfigFormat::from_path(path)?;
+        let contents = fs::read_to_string(path)?;
+        Self::parse(&contents, format, overrides)
+    }
+
+    pub fn parse(contents: &str, format: ConfigFormat, overrides: &ConfigOverrides) -> Result<Self> {
+        let mut raw: RawConfig = match format {
+            ConfigFormat::Json => {
+                let deserializer = &mut serde_json::Deserializer::from_str(contents);
+                serde_path_to_error::deserialize(deserializer).map_err(ParseError::from)?
+            }
+            ConfigFormat::Toml => {
+                let deserializer = toml::Deserializer::new(contents);
+                serde_path_to_error::deserialize(deserializer)
+                    .map_err(|error| toml_error(error, contents))?
+            }
+            ConfigFormat::Yaml => {
+                let deserializer = serde_yaml::Deserializer::from_str(contents);
+                serde_path_to_error::deserialize(deserializer).map_err(ParseError::from)?
+            }
+        };
+        raw.apply(overrides);
+        raw.into_config()
+    }
+
+    pub fn from_value(json_config: &Value) -> Result<Self> {
+        let raw: RawConfig = serde_path_to_error::deserialize(json_config).map_err(ParseError::from)?;
+        raw.into_config()
+    }
+}
+
+impl FromStr for Config {
+    type Err = Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        Self::parse(s, ConfigFormat::Json, &ConfigOverrides::default())
+    }
+}
+
+impl TryFrom<Value> for Config {
+    type Error = Error;
+
+    fn try_from(json_config: Value) -> Result<Self> {
+        Self::from_value(&json_config)
+    }
+}
@@ -4,9 +4,16 @@ use lazy_static::lazy_static;
 lazy_static!{
     pub static ref TWELVE_HOUR_TIME_RE: Regex = Regex::new(r"^([0-9]|1[012])(:[0-5]\d)?(am|pm|AM|PM)$").unwrap();
     pub static ref UNIT_CODE_RE: Regex = Regex::new(r"^([a-zA-Z]{4}\d{4})$").unwrap();
-    pub static ref SUBCODE_RE: Regex = Regex::new(r"^([a-zA-Z]{4}\d{4})-(\w+).+$").unwrap();
-    pub static ref SEMESTER_RE: Regex = Regex::new(r"^S([12]).+$").unwrap();
+    pub static ref SUBCODE_RE: Regex = Regex::new(
+        r"^([a-zA-Z]{4}\d{4})-([a-zA-Z0-9]+)(?:-([a-zA-Z0-9]+))?(?:-([a-zA-Z0-9]+))?(?:\s|$)"
+    ).unwrap();
+    pub static ref SESSION_CODE_RE: Regex = Regex::new(
+        r"^(?:S([12])C)?(?:I([A-Z]{2})|(SUMMER|WINTER|SUM|WIN)([EML])?)?$"
+    ).unwrap();
+    pub static ref SESSION_NAME_RE: Regex = Regex::new(r"^(semester|intensive|summer|winter)(?:\s+(\w+))?$").unwrap();
     pub static ref SEMESTER_KEY_RE: Regex = Regex::new(r"^Semester (\d+)$").unwrap();
+    pub static ref PLACEHOLDER_RE: Regex = Regex::new(r"\{(\w+)\}").unwrap();
+    pub static ref DURATION_RE: Regex = Regex::new(r"(?i)^(\d+(?:\.\d+)?)\s*(hours?|hrs?|minutes?|mins?)").unwrap();
 }
 
 pub const TIMED: bool = true;
@@ -14,6 +21,31 @@ pub const TIMED: bool = true;
 pub const DEFAULT_RUN_CHROMEDRIVER: bool = false;
 pub const DEFAULT_HEADLESS: bool = false;
 pub const DEFAULT_PORT: u16 = 9515;
+pub const DEFAULT_INTERVAL: u64 = 60;
+pub const DEFAULT_BACKOFF_MULTIPLIER: f64 = 2.0;
+pub const DEFAULT_MAX_INTERVAL: u64 = 900;
+pub const DEFAULT_AUDIO_REPEAT: u32 = 3;
+pub const MUSIC_NOTIFIER: &str = "music";
+pub const WEBHOOK_TIMEOUT_MS: u64 = 10_000;
+pub const DEFAULT_COOLDOWN_SECS: u64 = 300;
+
+pub const DEFAULT_HISTORY_FILE: &str = "history.jsonl";
+pub const DEFAULT_HISTORY_MAX_AGE_DAYS: u64 = 90;
+pub const HISTORY_PRUNE_INTERVAL: usize = 1_000;
+pub const REPORT_TREND_WINDOW: usize = 10;
+
+pub const DEFAULT_CALENDAR_FILE: &str = "allocations.ics";
+pub const ICS_LINE_LENGTH: usize = 75;
+
+pub const DEFAULT_MAX_ATTEMPTS: u32 = 3;
+pub const DEFAULT_INITIAL_DELAY_MS: u64 = 500;
+pub const DEFAULT_MAX_DELAY_MS: u64 = 10_000;
+pub const DEFAULT_MULTIPLIER: f64 = 2.0;
+pub const DEFAULT_JITTER: f64 = 0.2;
+pub const DEFAULT_MAX_CONSECUTIVE_FAILURES: u32 = 5;
+
+pub const CHROMEDRIVER_STARTUP_TIMEOUT_MS: u64 = 5_000;
+pub const CHROMEDRIVER_POLL_INTERVAL_MS: u64 = 100;
 
 pub const MIN_PORT: u16 = 1024;
 pub const MAX_PORT: u16 = 65535;
@@ -21,6 +53,9 @@ pub const LOCALHOST: &str = "127.0.0.1";
 
 pub const CONFIG_FILE: &str = "config.json";
 pub const ROWS_IN_TABLE: usize = 12;
+/// The latest `start_after`, in minutes since midnight, that the timetable can still filter by
+/// once it is moved one hour ahead
+pub const LATEST_START_AFTER: u32 = 22 * 60;
 
 pub const PUBLIC_TIMETABLE_ODD: &str = "https://timetable.sydney.edu.au/odd/timetable/#subjects";
 pub const PUBLIC_TIMETABLE_EVEN: &str = "https://timetable.sydney.edu.au/even/timetable/#subjects";
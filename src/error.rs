@@ -1,6 +1,110 @@
+use std::io;
+use std::process::ExitStatus;
+
+use thirtyfour::error::WebDriverError;
 use thiserror::Error;
 
-use crate::allocation::Semester;
+use crate::session::Session;
+
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+/// Messages chromedriver reports when the browser has crashed or been closed.
+const SESSION_LOST_MESSAGES: [&str; 5] = [
+    "chrome not reachable",
+    "disconnected",
+    "session deleted",
+    "tab crashed",
+    "target window already closed",
+];
+
+/// Every error that seatfinder can produce.
+#[derive(Error, Debug)]
+pub enum Error {
+    #[error(transparent)]
+    Parse(#[from] ParseError),
+    #[error(transparent)]
+    Table(#[from] TableError),
+    #[error(transparent)]
+    TableRow(#[from] TableRowError),
+    #[error(transparent)]
+    Offering(#[from] OfferingError),
+    #[error(transparent)]
+    Audio(#[from] AudioError),
+    #[error(transparent)]
+    Notify(#[from] NotifyError),
+    #[error("webdriver error: {}", .0)]
+    WebDriver(Box<WebDriverError>),
+    #[error("io error: {}", .0)]
+    Io(#[from] io::Error),
+    #[error("could not serialize to JSON: {}", .0)]
+    Serialize(#[from] serde_json::Error),
+    #[error("could not find an available port")]
+    NoAvailablePort,
+}
+
+impl From<WebDriverError> for Error {
+    fn from(error: WebDriverError) -> Self {
+        Self::WebDriver(Box::new(error))
+    }
+}
+
+impl Error {
+    /// Whether the operation that caused this error may succeed if it is
+    /// attempted again, e.g. because the page had not finished loading.
+    pub fn is_retryable(&self) -> bool {
+        if self.is_session_lost() {
+            return false;
+        }
+
+        match self {
+            Self::Table(_) | Self::TableRow(_) => true,
+            Self::Notify(e) => e.is_retryable(),
+            Self::WebDriver(e) => matches!(
+                **e,
+                WebDriverError::NotFound(..)
+                    | WebDriverError::Timeout(_)
+                    | WebDriverError::RequestFailed(_)
+                    | WebDriverError::HttpError(_)
+                    | WebDriverError::ElementClickIntercepted(_)
+                    | WebDriverError::ElementNotInteractable(_)
+                    | WebDriverError::InvalidElementState(_)
+                    | WebDriverError::MoveTargetOutOfBounds(_)
+                    | WebDriverError::NoSuchElement(_)
+                    | WebDriverError::ScriptTimeout(_)
+                    | WebDriverError::StaleElementReference(_)
+                    | WebDriverError::WebDriverTimeout(_)
+                    | WebDriverError::UnknownError(_)
+            ),
+            Self::Parse(_) 
+            | Self::Offering(_) 
+            | Self::Audio(_) 
+            | Self::Io(_) 
+            | Self::Serialize(_) 
+            | Self::NoAvailablePort => false,
+        }
+    }
+
+    /// Whether the browser or chromedriver is no longer reachable, in which case
+    /// nothing can succeed until a new session is created.
+    pub fn is_session_lost(&self) -> bool {
+        let Self::WebDriver(e) = self else {
+            return false;
+        };
+
+        match **e {
+            WebDriverError::InvalidSessionId(_) | WebDriverError::SessionNotCreated(_) => true,
+            WebDriverError::RequestFailed(ref message) | WebDriverError::HttpError(ref message) => {
+                let message = message.to_ascii_lowercase();
+                message.contains("connection refused") || message.contains("error sending request")
+            }
+            WebDriverError::NoSuchWindow(ref info) | WebDriverError::UnknownError(ref info) => {
+                let message = info.value.message.to_ascii_lowercase();
+                SESSION_LOST_MESSAGES.iter().any(|lost| message.contains(lost))
+            }
+            _ => false,
+        }
+    }
+}
 
 #[derive(Error, Debug)]
 pub enum TableRowError {
@@ -22,23 +126,44 @@ pub enum TableError {
 
 #[derive(Error, Debug)]
 pub enum ParseError {
-    #[error("an invalid JSON value was encountered")]
-    ParseJsonError,
-    #[error("parity must be even or odd")]
-    ParseParityError,
+    #[error("invalid config at `{path}`: {message}")]
+    ParseConfigError { 
+        path: String, 
+        message: String, 
+        line: Option<usize>, 
+        column: Option<usize>,
+    },
     #[error("day with isoweekday {:?} is invalid; isoweekday must be between 1 and 7", .0)]
     ParseDayIsoError(u64),
     #[error("{:?} is not a day of the week", .0)]
     ParseDayStrError(String),
-    #[error("invalid semester {:?}: semester must be either 1 or 2", .0)]
+    #[error("invalid semester {:?}; semester must be 0 (any), 1 or 2", .0)]
     ParseSemesterError(u64),
     #[error("invalid semester {:?}", .0)]
     ParseSemesterStrError(String),
+    #[error("invalid session {:?}; expected a semester (0, 1 or 2), a session code such as \"S1CIJA\", \"intensive\", \"summer\" or \"winter\"", .0)]
+    ParseSessionError(String),
+    #[error("{:?} is not a valid number", .0)]
+    ParseNumberError(String),
     #[error("{:?} cannot be converted into 24 hour time", .0)]
     ParseTimeError(String),
+    #[error("invalid activity {:?}; expected an activity number, a range such as \"3-6\" or \"any\"", .0)]
+    ParseActivityError(String),
+    #[error("invalid weeks {:?}; expected weeks such as \"1-6, 8-13\"", .0)]
+    ParseWeeksError(String),
     #[error("invalid activity type {:?}", .0)]
     ParseActivityTypeError(String),
-    #[error("an invalid query was encountered.")]
+    #[error("unsupported config format {:?}; expected one of .json, .toml, .yaml or .yml", .0)]
+    ParseConfigFormatError(String),
+    #[error("notifier {:?} is not defined in `notifiers`", .0)]
+    ParseNotifierError(String),
+    #[error("group {:?} has no alternatives", .0)]
+    ParseEmptyGroupError(String),
+    #[error("group {:?} is defined more than once", .0)]
+    ParseDuplicateGroupError(String),
+    #[error("subcode {subcode:?} is not an offering of {unit_code:?}")]
+    ParseSubcodeUnitError { subcode: String, unit_code: String },
+    #[error("either `query`, `queries` or `groups` must be specified")]
     ParseQueriesError,
     #[error("regex {:?} did not match {:?}", .0, .1)]
     RegexNoMatch(&'static str, String),
@@ -46,12 +171,80 @@ pub enum ParseError {
 
 #[derive(Error, Debug)]
 pub enum OfferingError {
-    #[error("offering {:?} in semester {:?} is improperly formatted", .0, .1)]
-    SemesterFormatError(String, String),
-    #[error("expected semester {expected}, found semester {actual}")]
-    SemesterInvalid { expected: Semester, actual: Semester },
+    #[error("expected session {expected}, found session {actual}")]
+    SessionInvalid { expected: Session, actual: Session },
     #[error("no offerings found for {:?}", .0)]
     NoOfferingsError(String),
     #[error("no valid sessions found for {:?}", .0)]
     NoValidOfferingsError(String),
+    #[error("{:?} is not offered, found {}", .0, .1.join(", "))]
+    SubcodeNotOffered(String, Vec<String>),
+}
+
+#[derive(Error, Debug)]
+pub enum AudioError {
+    #[error("no audio output device is available: {}", .0)]
+    Stream(#[from] rodio::StreamError),
+    #[error("could not decode audio: {}", .0)]
+    Decoder(#[from] rodio::decoder::DecoderError),
+    #[error("could not play audio: {}", .0)]
+    Play(#[from] rodio::PlayError),
+}
+
+#[derive(Error, Debug)]
+pub enum NotifyError {
+    #[error("command {:?} exited with {}", .0, .1)]
+    CommandStatus(String, ExitStatus),
+    #[error("could not show desktop notification: {}", .0)]
+    Desktop(#[from] notify_rust::error::Error),
+    #[error("webhook request failed: {}", .0)]
+    Http(#[from] reqwest::Error),
+    #[error("webhook responded with status {}", .0)]
+    HttpStatus(u16),
+    #[error("invalid email address: {}", .0)]
+    Address(#[from] lettre::address::AddressError),
+    #[error("could not build email: {}", .0)]
+    Email(#[from] lettre::error::Error),
+    #[error("could not send email: {}", .0)]
+    Smtp(#[from] lettre::transport::smtp::Error),
+    #[error("notifier task failed: {}", .0)]
+    Task(#[from] tokio::task::JoinError),
+}
+
+impl NotifyError {
+    /// Whether the notification may go through if it is sent again, i.e. the
+    /// receiving server was unreachable or failed with a 5xx status.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            Self::Http(e) => e.is_connect() || e.is_timeout(),
+            Self::HttpStatus(status) => (500..600).contains(status),
+            _ => false,
+        }
+    }
+}
+
+impl From<serde_path_to_error::Error<serde_json::Error>> for ParseError {
+    fn from(error: serde_path_to_error::Error<serde_json::Error>) -> Self {
+        let path = error.path().to_string();
+        let error = error.into_inner();
+        let (line, column) = match error.line() {
+            0 => (None, None),
+            line => (Some(line), Some(error.column())),
+        };
+
+        ParseError::ParseConfigError { path, message: error.to_string(), line, column }
+    }
+}
+
+impl From<serde_path_to_error::Error<serde_yaml::Error>> for ParseError {
+    fn from(error: serde_path_to_error::Error<serde_yaml::Error>) -> Self {
+        let path = error.path().to_string();
+        let error = error.into_inner();
+        let (line, column) = match error.location() {
+            Some(location) => (Some(location.line()), Some(location.column())),
+            None => (None, None),
+        };
+
+        ParseError::ParseConfigError { path, message: error.to_string(), line, column }
+    }
 }
\ No newline at end of file
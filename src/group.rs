@@ -0,0 +1,131 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::query::FinderQuery;
+use crate::tracker::Transition;
+
+/// An ordered list of alternative queries, of which the first with seats is wanted,
+/// e.g. tutorial 3, otherwise tutorial 5, otherwise any Thursday tutorial.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct QueryGroup {
+    pub name: String,
+    pub alternatives: Vec<FinderQuery>,
+}
+
+impl QueryGroup {
+    /// The alternatives of the group, each marked with its place in the group.
+    pub fn into_queries(self) -> Vec<FinderQuery> {
+        let of = self.alternatives.len();
+        self.alternatives
+            .into_iter()
+            .enumerate()
+            .map(|(index, mut query)| {
+                query.alternative = Some(Alternative { group: self.name.clone(), rank: index + 1, of });
+                query
+            })
+            .collect()
+    }
+}
+
+/// The place of a query in a [group](QueryGroup), where rank 1 is the most preferred.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct Alternative {
+    pub group: String,
+    pub rank: usize,
+    pub of: usize,
+}
+
+impl fmt::Display for Alternative {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "alternative {} of {} in {:?}", self.rank, self.of, self.group)
+    }
+}
+
+/// Whether a change in the seats of an alternative should be notified about.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Resolution {
+    /// Notify, noting the rank of the alternative that was matched before if this one is better
+    Notify { better_than: Option<usize> },
+    /// A better alternative of the group has seats, so this one is not wanted
+    Suppress,
+}
+
+#[derive(Debug, Default)]
+struct GroupState {
+    /// Whether each alternative had seats when it was last searched for
+    available: Vec<bool>,
+    /// The rank of the alternative that was last notified about having seats
+    resolved: Option<usize>,
+}
+
+/// Resolves every group to its most preferred alternative with seats across polls.
+#[derive(Debug, Default)]
+pub struct GroupResolver {
+    groups: HashMap<String, GroupState>,
+}
+
+impl GroupResolver {
+    pub fn new(queries: &[FinderQuery]) -> Self {
+        let mut resolver = Self::default();
+        resolver.reload(queries);
+        resolver
+    }
+
+    /// Forgets groups that are no longer searched for or whose alternatives changed in number.
+    pub fn reload(&mut self, queries: &[FinderQuery]) {
+        let mut sizes = HashMap::new();
+        for alternative in queries.iter().filter_map(|query| query.alternative.as_ref()) {
+            sizes.insert(alternative.group.clone(), alternative.of);
+        }
+
+        self.groups.retain(|group, state| sizes.get(group) == Some(&state.available.len()));
+        for (group, of) in sizes {
+            self.groups.entry(group).or_insert_with(|| GroupState { available: vec![false; of], resolved: None });
+        }
+    }
+
+    /// Records whether the alternative had seats when it was last searched for.
+    pub fn update(&mut self, alternative: &Alternative, available: bool) {
+        if let Some(state) = self.groups.get_mut(&alternative.group) {
+            if let Some(slot) = state.available.get_mut(alternative.rank - 1) {
+                *slot = available;
+            }
+        }
+    }
+
+    /// The rank of the most preferred alternative of `group` with seats.
+    pub fn best(&self, group: &str) -> Option<usize> {
+        let state = self.groups.get(group)?;
+        state.available.iter().position(|&available| available).map(|index| index + 1)
+    }
+
+    /// Decides whether a transition of an alternative is worth notifying about.
+    pub fn resolve(&mut self, alternative: &Alternative, transition: Transition) -> Resolution {
+        let best = self.best(&alternative.group);
+        let Some(state) = self.groups.get_mut(&alternative.group) else {
+            return Resolution::Notify { better_than: None };
+        };
+
+        if transition == Transition::SoldOut {
+            // Only the loss of the alternative that was matched is of interest
+            if state.resolved != Some(alternative.rank) {
+                return Resolution::Suppress;
+            }
+            state.resolved = None;
+            return Resolution::Notify { better_than: None };
+        }
+
+        if best.is_some_and(|best| best < alternative.rank) {
+            return Resolution::Suppress;
+        }
+
+        let better_than = state.resolved.filter(|&resolved| resolved > alternative.rank);
+        if state.resolved.is_none_or(|resolved| resolved >= alternative.rank) {
+            state.resolved = Some(alternative.rank);
+        }
+        Resolution::Notify { better_than }
+    }
+}
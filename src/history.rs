@@ -0,0 +1,257 @@
+use std::fs::{self, File, OpenOptions};
+use std::io::{BufRead, BufReader, BufWriter, Write};
+use std::path::PathBuf;
+
+use chrono::{DateTime, Duration, Local};
+use log::{debug, warn};
+use serde::{Deserialize, Serialize};
+
+use crate::allocation::{ActivityType, Allocation, Day, TwentyFourHourTime};
+use crate::session::{deserialize_session_label, Session};
+use crate::consts::{DEFAULT_HISTORY_FILE, DEFAULT_HISTORY_MAX_AGE_DAYS, HISTORY_PRUNE_INTERVAL};
+use crate::error::Result;
+use crate::query::FinderQuery;
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct HistoryConfig {
+    /// Whether scraped allocations are recorded at all, which is off unless asked for
+    pub enabled: bool,
+    /// The JSON Lines file that observations are appended to
+    pub path: PathBuf,
+    /// Observations older than this many days are removed
+    pub max_age: Option<u64>,
+    /// Only this many of the most recent observations are kept
+    pub max_entries: Option<usize>,
+}
+
+impl Default for HistoryConfig {
+    fn default() -> Self {
+        Self {
+            enabled: false,
+            path: PathBuf::from(DEFAULT_HISTORY_FILE),
+            max_age: Some(DEFAULT_HISTORY_MAX_AGE_DAYS),
+            max_entries: None,
+        }
+    }
+}
+
+/// Identifies an allocation across observations. Offerings of a unit in different
+/// sessions number their activities separately, so the session is part of the key.
+#[derive(Debug, Clone, PartialEq)]
+pub struct AllocationKey {
+    pub unit_code: String,
+    pub semester: Session,
+    pub activity_type: ActivityType,
+    pub activity: u64,
+}
+
+impl AllocationKey {
+    pub fn new(unit_code: &str, allocation: &Allocation) -> Self {
+        Self {
+            unit_code: unit_code.to_owned(),
+            semester: allocation.semester.clone(),
+            activity_type: allocation.activity_type,
+            activity: allocation.activity,
+        }
+    }
+}
+
+/// The seats of an allocation at the time it was scraped.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Observation {
+    pub timestamp: DateTime<Local>,
+    pub unit_code: String,
+    pub activity_type: ActivityType,
+    pub activity: u64,
+    pub seats: i16,
+    #[serde(deserialize_with = "deserialize_session_label")]
+    pub semester: Session,
+    pub day: Day,
+    pub time: TwentyFourHourTime,
+    pub location: String,
+}
+
+impl Observation {
+    pub fn new(unit_code: &str, allocation: &Allocation) -> Self {
+        Self {
+            timestamp: Local::now(),
+            unit_code: unit_code.to_owned(),
+            activity_type: allocation.activity_type,
+            activity: allocation.activity,
+            seats: allocation.seats,
+            semester: allocation.semester.clone(),
+            day: allocation.day,
+            time: allocation.time,
+            location: allocation.location.clone(),
+        }
+    }
+
+    pub fn key(&self) -> AllocationKey {
+        AllocationKey {
+            unit_code: self.unit_code.clone(),
+            semester: self.semester.clone(),
+            activity_type: self.activity_type,
+            activity: self.activity,
+        }
+    }
+
+    /// Whether the observed allocation is one that `query` searches for.
+    pub fn matches(&self, query: &FinderQuery) -> bool {
+        let session = query.subcode.as_ref().map_or(&query.session, |subcode| subcode.session());
+        self.unit_code == query.unit_code
+            && session.matches(&self.semester)
+            && self.activity_type == query.activity_type
+            && query.activity.matches(self.activity)
+            && query.days.contains(&self.day)
+    }
+}
+
+/// An append-only JSON Lines file of [observations](Observation).
+#[derive(Debug)]
+pub struct HistoryStore {
+    config: HistoryConfig,
+    recorded: usize,
+}
+
+impl HistoryStore {
+    /// Opens the store, removing observations that are past the retention limits.
+    pub fn open(config: HistoryConfig) -> Result<Self> {
+        if let Some(parent) = config.path.parent().filter(|parent| !parent.as_os_str().is_empty()) {
+            fs::create_dir_all(parent)?;
+        }
+
+        let store = Self { config, recorded: 0 };
+        let removed = store.prune()?;
+        if removed > 0 {
+            debug!("Removed {} observations from {}", removed, store.config.path.display());
+        }
+        Ok(store)
+    }
+
+    /// Opens the store for reading only, without applying the retention limits.
+    pub fn reader(config: HistoryConfig) -> Self {
+        Self { config, recorded: 0 }
+    }
+
+    pub fn config(&self) -> &HistoryConfig {
+        &self.config
+    }
+
+    pub fn record(&mut self, observation: &Observation) -> Result<()> {
+        let mut line = serde_json::to_string(observation)?;
+        line.push('\n');
+
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&self.config.path)?;
+        file.write_all(line.as_bytes())?;
+
+        // Pruning rewrites the whole file, so it only happens every so often
+        self.recorded += 1;
+        if self.recorded.is_multiple_of(HISTORY_PRUNE_INTERVAL) {
+            self.prune()?;
+        }
+        Ok(())
+    }
+
+    /// Every observation in the order it was recorded. Lines that cannot be
+    /// parsed (e.g. a line cut short by a crash) are skipped.
+    pub fn read(&self) -> Result<Vec<Observation>> {
+        let file = match File::open(&self.config.path) {
+            Ok(file) => file,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => return Ok(Vec::new()),
+            Err(e) => return Err(e.into()),
+        };
+
+        let mut observations = Vec::new();
+        for (index, line) in BufReader::new(file).lines().enumerate() {
+            let line = line?;
+            if line.trim().is_empty() {
+                continue;
+            }
+
+            match serde_json::from_str(&line) {
+                Ok(observation) => observations.push(observation),
+                Err(e) => warn!(
+                    "Skipping line {} of {}: {}",
+                    index + 1,
+                    self.config.path.display(),
+                    e
+                ),
+            }
+        }
+        Ok(observations)
+    }
+
+    /// The observations of allocations that `query` searches for.
+    pub fn for_query(&self, query: &FinderQuery) -> Result<Vec<Observation>> {
+        let mut observations = self.read()?;
+        observations.retain(|observation| observation.matches(query));
+        Ok(observations)
+    }
+
+    pub fn for_allocation(&self, key: &AllocationKey) -> Result<Vec<Observation>> {
+        let mut observations = self.read()?;
+        observations.retain(|observation| observation.key() == *key);
+        Ok(observations)
+    }
+
+    /// Removes observations that are past the retention limits, returning how many were removed.
+    pub fn prune(&self) -> Result<usize> {
+        let observations = self.read()?;
+        let total = observations.len();
+
+        let cutoff = self.config.max_age.map(|days| Local::now() - Duration::days(days as i64));
+        let mut kept: Vec<Observation> = observations
+            .into_iter()
+            .filter(|observation| cutoff.is_none_or(|cutoff| observation.timestamp >= cutoff))
+            .collect();
+        if let Some(max_entries) = self.config.max_entries {
+            let excess = kept.len().saturating_sub(max_entries);
+            kept.drain(..excess);
+        }
+
+        let removed = total - kept.len();
+        if removed == 0 {
+            return Ok(0);
+        }
+
+        // The file is replaced in one step so that a crash never leaves it half written
+        let temporary = self.config.path.with_extension("jsonl.tmp");
+        let mut writer = BufWriter::new(File::create(&temporary)?);
+        for observation in kept.iter() {
+            let line = serde_json::to_string(observation)?;
+            writeln!(writer, "{}", line)?;
+        }
+        writer.flush()?;
+        drop(writer);
+        fs::rename(&temporary, &self.config.path)?;
+
+        Ok(removed)
+    }
+}
+
+/// The observations at which the seats of an allocation changed, including the
+/// first observation of every allocation.
+pub fn seat_changes(observations: &[Observation]) -> Vec<&Observation> {
+    let mut last_seen: Vec<(AllocationKey, i16)> = Vec::new();
+    let mut changes = Vec::new();
+
+    for observation in observations.iter() {
+        let key = observation.key();
+        match last_seen.iter_mut().find(|(seen, _)| *seen == key) {
+            Some((_, seats)) if *seats == observation.seats => {}
+            Some((_, seats)) => {
+                *seats = observation.seats;
+                changes.push(observation);
+            }
+            None => {
+                last_seen.push((key, observation.seats));
+                changes.push(observation);
+            }
+        }
+    }
+    changes
+}
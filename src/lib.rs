@@ -1,10 +1,29 @@
 mod consts;
-mod error;
+pub mod error;
 #[macro_use]
 mod macros;
-mod query;
+pub mod query;
+pub mod config;
 mod selector;
 mod methods;
 mod searcher;
-mod allocation;
-pub mod seatfinder;
\ No newline at end of file
+pub mod allocation;
+pub mod session;
+pub mod offering;
+pub mod reload;
+pub mod check;
+pub mod outcome;
+pub mod retry;
+pub mod notifier;
+pub mod tracker;
+pub mod group;
+pub mod planner;
+pub mod calendar;
+pub mod history;
+pub mod report;
+pub mod listing;
+pub mod schedule;
+pub mod seatfinder;
+pub mod cli;
+
+pub use error::{Error, Result};
\ No newline at end of file
@@ -0,0 +1,169 @@
+use std::cmp::Reverse;
+use std::io::{self, BufRead, Write};
+
+use clap::ValueEnum;
+
+use crate::allocation::{ActivityType, Allocation};
+use crate::offering::Offering;
+use crate::session::Session;
+use crate::report::{csv_field, print_columns, ReportFormat};
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum ListSort {
+    /// By day and then by time
+    Day,
+    /// By time and then by day
+    Time,
+    /// From most to fewest seats left
+    Seats,
+}
+
+#[derive(Debug, Clone)]
+pub struct ListOptions {
+    pub unit_code: String,
+    pub session: Session,
+    /// The exact offering to list, which takes precedence over `session`
+    pub subcode: Option<Offering>,
+    /// Only list activities of this type
+    pub activity_type: Option<ActivityType>,
+    pub sort: ListSort,
+    pub format: ReportFormat,
+}
+
+impl ListOptions {
+    /// Drops the allocations of other activity types and sorts the rest.
+    pub fn apply(&self, allocations: &mut Vec<Allocation>) {
+        if let Some(activity_type) = self.activity_type {
+            allocations.retain(|allocation| allocation.activity_type == activity_type);
+        }
+
+        match self.sort {
+            ListSort::Day => allocations.sort_by_key(|allocation| (allocation.day as u8, allocation.time)),
+            ListSort::Time => allocations.sort_by_key(|allocation| (allocation.time, allocation.day as u8)),
+            ListSort::Seats => allocations.sort_by_key(|allocation| {
+                (Reverse(allocation.seats), allocation.day as u8, allocation.time)
+            }),
+        }
+    }
+}
+
+const COLUMNS: [&str; 8] = [
+    "day", 
+    "time", 
+    "duration", 
+    "activity", 
+    "seats", 
+    "weeks", 
+    "campus", 
+    "location"
+];
+
+fn row(allocation: &Allocation) -> [String; 8] {
+    let day: &str = allocation.day.into();
+    [
+        day.to_owned(),
+        allocation.time.to_string(),
+        allocation.duration.to_string(),
+        format!("{} {}", allocation.activity_type.checkbox_id_suffix(), allocation.activity),
+        allocation.seats.to_string(),
+        allocation.weeks.to_string(),
+        allocation.campus.clone(),
+        allocation.location.clone(),
+    ]
+}
+
+pub fn print_table(allocations: &[Allocation]) {
+    if allocations.is_empty() {
+        return;
+    }
+
+    let rows: Vec<[String; 8]> = allocations.iter().map(row).collect();
+    print_columns(&COLUMNS, &rows);
+}
+
+pub fn print_csv(allocations: &[Allocation]) {
+    print!("{}", csv(allocations));
+}
+
+/// The allocations as CSV with a header row, one line per allocation.
+pub fn csv(allocations: &[Allocation]) -> String {
+    let mut csv = String::from("day,time,duration,activity_type,group,activity,description,seats,weeks,campus,location\n");
+
+    for allocation in allocations.iter() {
+        let day: &str = allocation.day.into();
+        let fields = [
+            day.to_owned(),
+            allocation.time.to_string(),
+            allocation.duration.to_string(),
+            allocation.activity_type.checkbox_id_suffix().to_owned(),
+            allocation.group.clone(),
+            allocation.activity.to_string(),
+            allocation.description.clone(),
+            allocation.seats.to_string(),
+            allocation.weeks.to_string(),
+            allocation.campus.clone(),
+            allocation.location.clone(),
+        ];
+        let fields: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+pub fn print_json(allocations: &[Allocation]) -> serde_json::Result<()> {
+    println!("{}", json(allocations)?);
+    Ok(())
+}
+
+/// The allocations as a pretty-printed JSON array.
+pub fn json(allocations: &[Allocation]) -> serde_json::Result<String> {
+    serde_json::to_string_pretty(allocations)
+}
+
+fn offering_row(index: usize, subcode: &str) -> [String; 4] {
+    let (code, session) = match subcode.parse::<Offering>() {
+        Ok(offering) => (offering.session_code().to_owned(), offering.session().to_string()),
+        Err(_) => ("?".to_owned(), "-".to_owned()),
+    };
+    [(index + 1).to_string(), subcode.to_owned(), code, session]
+}
+
+/// Prints the offerings of a unit, numbered from 1, with their session codes and sessions.
+pub fn print_offerings(subcodes: &[String]) {
+    let rows: Vec<[String; 4]> = subcodes
+        .iter()
+        .enumerate()
+        .map(|(index, subcode)| offering_row(index, subcode))
+        .collect();
+    print_columns(&["#", "subcode", "code", "session"], &rows);
+}
+
+/// Asks for the number of one of the offerings on stdin until a valid one (or nothing) is given.
+pub fn choose_offering(subcodes: &[String]) -> io::Result<Option<Offering>> {
+    let stdin = io::stdin();
+    let mut lines = stdin.lock().lines();
+
+    loop {
+        print!("Choose an offering [1-{}]: ", subcodes.len());
+        io::stdout().flush()?;
+
+        let Some(line) = lines.next().transpose()? else {
+            return Ok(None);
+        };
+        let line = line.trim();
+        if line.is_empty() {
+            return Ok(None);
+        }
+
+        let chosen = line
+            .parse::<usize>()
+            .ok()
+            .and_then(|number| subcodes.get(number.checked_sub(1)?))
+            .and_then(|subcode| subcode.parse::<Offering>().ok());
+        match chosen {
+            Some(offering) => return Ok(Some(offering)),
+            None => println!("{:?} is not the number of an offering", line),
+        }
+    }
+}
@@ -1,5 +1,5 @@
-use seatfinder::seatfinder;
+use seatfinder::cli;
 
 fn main() {
-    seatfinder::run_every(60);
+    cli::run();
 }
\ No newline at end of file
@@ -0,0 +1,49 @@
+use std::fs::File;
+use std::io::BufReader;
+use std::path::PathBuf;
+
+use futures::future::{BoxFuture, FutureExt};
+
+use crate::error::{AudioError, NotifyError, Result};
+use super::{Notification, Notifier};
+
+/// Plays an MP3 file a bounded number of times.
+pub struct AudioNotifier {
+    path: PathBuf,
+    repeat: u32,
+}
+
+impl AudioNotifier {
+    pub fn new(path: PathBuf, repeat: u32) -> Self {
+        Self { path, repeat }
+    }
+}
+
+impl Notifier for AudioNotifier {
+    fn notify<'a>(&'a self, _notification: &'a Notification) -> BoxFuture<'a, Result<()>> {
+        let path = self.path.clone();
+        let repeat = self.repeat;
+
+        async move {
+            // rodio blocks until playback finishes, so it is kept off the runtime's threads
+            tokio::task::spawn_blocking(move || play(&path, repeat))
+                .await
+                .map_err(NotifyError::from)?
+        }
+        .boxed()
+    }
+}
+
+fn play(path: &PathBuf, repeat: u32) -> Result<()> {
+    let (_stream, stream_handle) = rodio::OutputStream::try_default().map_err(AudioError::from)?;
+    let sink = rodio::Sink::try_new(&stream_handle).map_err(AudioError::from)?;
+
+    for _ in 0..repeat {
+        let file = BufReader::new(File::open(path)?);
+        let source = rodio::Decoder::new_mp3(file).map_err(AudioError::from)?;
+        sink.append(source);
+    }
+
+    sink.sleep_until_end();
+    Ok(())
+}
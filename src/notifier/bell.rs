@@ -0,0 +1,20 @@
+use std::io::{self, Write};
+
+use futures::future::{self, BoxFuture, FutureExt};
+
+use crate::error::Result;
+use super::{Notification, Notifier};
+
+/// Rings the terminal bell.
+pub struct BellNotifier;
+
+impl Notifier for BellNotifier {
+    fn notify<'a>(&'a self, _notification: &'a Notification) -> BoxFuture<'a, Result<()>> {
+        let mut stdout = io::stdout();
+        let result = stdout
+            .write_all(b"\x07")
+            .and_then(|()| stdout.flush())
+            .map_err(Into::into);
+        future::ready(result).boxed()
+    }
+}
@@ -0,0 +1,43 @@
+use futures::future::{BoxFuture, FutureExt};
+use tokio::process::Command;
+
+use crate::error::{NotifyError, Result};
+use super::{Notification, Notifier};
+
+/// Runs a program, passing the details of the notification through
+/// `SEATFINDER_*` environment variables.
+pub struct CommandNotifier {
+    program: String,
+    args: Vec<String>,
+}
+
+impl CommandNotifier {
+    pub fn new(program: String, args: Vec<String>) -> Self {
+        Self { program, args }
+    }
+}
+
+impl Notifier for CommandNotifier {
+    fn notify<'a>(&'a self, notification: &'a Notification) -> BoxFuture<'a, Result<()>> {
+        async move {
+            let allocation = &notification.allocation;
+            let status = Command::new(&self.program)
+                .args(&self.args)
+                .env("SEATFINDER_TITLE", notification.title())
+                .env("SEATFINDER_MESSAGE", notification.message())
+                .env("SEATFINDER_EVENT", notification.transition.event())
+                .env("SEATFINDER_UNIT_CODE", &notification.query.unit_code)
+                .env("SEATFINDER_ACTIVITY_TYPE", allocation.activity_type.checkbox_id_suffix())
+                .env("SEATFINDER_ACTIVITY", allocation.activity.to_string())
+                .env("SEATFINDER_SEATS", allocation.seats.to_string())
+                .status()
+                .await?;
+
+            if !status.success() {
+                return Err(NotifyError::CommandStatus(self.program.clone(), status).into());
+            }
+            Ok(())
+        }
+        .boxed()
+    }
+}
@@ -0,0 +1,30 @@
+use futures::future::{BoxFuture, FutureExt};
+
+use crate::error::{NotifyError, Result};
+use super::{Notification, Notifier};
+
+/// Shows a desktop notification, which on Linux is sent over D-Bus.
+pub struct DesktopNotifier;
+
+impl Notifier for DesktopNotifier {
+    fn notify<'a>(&'a self, notification: &'a Notification) -> BoxFuture<'a, Result<()>> {
+        let title = notification.title();
+        let message = notification.message();
+
+        async move {
+            tokio::task::spawn_blocking(move || {
+                notify_rust::Notification::new()
+                    .appname("seatfinder")
+                    .summary(&title)
+                    .body(&message)
+                    .show()
+                    .map(|_| ())
+                    .map_err(NotifyError::from)
+            })
+            .await
+            .map_err(NotifyError::from)??;
+            Ok(())
+        }
+        .boxed()
+    }
+}
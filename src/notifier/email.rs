@@ -0,0 +1,96 @@
+use futures::future::{BoxFuture, FutureExt};
+use lettre::message::Mailbox;
+use lettre::transport::smtp::authentication::Credentials;
+use lettre::{AsyncSmtpTransport, AsyncTransport, Message, Tokio1Executor};
+use serde::Deserialize;
+
+use crate::error::{NotifyError, Result};
+use super::{Notification, Notifier};
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum EmailSecurity {
+    /// Upgrade the connection with STARTTLS (usually port 587)
+    #[default]
+    Starttls,
+    /// Connect over TLS (usually port 465)
+    Tls,
+    /// Send in plain text, e.g. to a relay on the local machine
+    None,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct EmailConfig {
+    pub host: String,
+    #[serde(default)]
+    pub port: Option<u16>,
+    #[serde(default)]
+    pub security: EmailSecurity,
+    #[serde(default)]
+    pub username: Option<String>,
+    #[serde(default)]
+    pub password: Option<String>,
+    pub from: String,
+    pub to: Vec<String>,
+}
+
+/// Sends an email over SMTP.
+pub struct EmailNotifier {
+    config: EmailConfig,
+}
+
+impl EmailNotifier {
+    pub fn new(config: EmailConfig) -> Self {
+        Self { config }
+    }
+
+    fn transport(&self) -> Result<AsyncSmtpTransport<Tokio1Executor>> {
+        let host = self.config.host.as_str();
+        let mut builder = match self.config.security {
+            EmailSecurity::Starttls => AsyncSmtpTransport::<Tokio1Executor>::starttls_relay(host)
+                .map_err(NotifyError::from)?,
+            EmailSecurity::Tls => AsyncSmtpTransport::<Tokio1Executor>::relay(host)
+                .map_err(NotifyError::from)?,
+            EmailSecurity::None => AsyncSmtpTransport::<Tokio1Executor>::builder_dangerous(host),
+        };
+
+        if let Some(port) = self.config.port {
+            builder = builder.port(port);
+        }
+
+        if let (Some(username), Some(password)) = (&self.config.username, &self.config.password) {
+            builder = builder.credentials(Credentials::new(username.clone(), password.clone()));
+        }
+
+        Ok(builder.build())
+    }
+
+    fn message(&self, notification: &Notification) -> Result<Message> {
+        let from: Mailbox = self.config.from.parse().map_err(NotifyError::from)?;
+        let mut builder = Message::builder()
+            .from(from)
+            .subject(notification.title());
+
+        for to in self.config.to.iter() {
+            let to: Mailbox = to.parse().map_err(NotifyError::from)?;
+            builder = builder.to(to);
+        }
+
+        Ok(builder.body(notification.message()).map_err(NotifyError::from)?)
+    }
+}
+
+impl Notifier for EmailNotifier {
+    fn notify<'a>(&'a self, notification: &'a Notification) -> BoxFuture<'a, Result<()>> {
+        async move {
+            let message = self.message(notification)?;
+            self.transport()?
+                .send(message)
+                .await
+                .map_err(NotifyError::from)?;
+            Ok(())
+        }
+        .boxed()
+    }
+}
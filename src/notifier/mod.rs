@@ -0,0 +1,197 @@
+mod audio;
+mod bell;
+mod command;
+mod desktop;
+mod email;
+mod webhook;
+
+use std::collections::{BTreeMap, HashMap};
+use std::fmt;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+
+use futures::future::BoxFuture;
+use log::{error, info};
+use serde::Deserialize;
+use tokio::task::JoinHandle;
+
+use crate::allocation::Allocation;
+use crate::consts::DEFAULT_AUDIO_REPEAT;
+use crate::error::Result;
+use crate::planner::Clash;
+use crate::query::FinderQuery;
+use crate::tracker::Transition;
+
+pub use audio::AudioNotifier;
+pub use bell::BellNotifier;
+pub use command::CommandNotifier;
+pub use desktop::DesktopNotifier;
+pub use email::{EmailConfig, EmailNotifier, EmailSecurity};
+pub use webhook::{render, WebhookConfig, WebhookNotifier, WebhookPayload};
+
+/// A change in the seats of an allocation along with the query that found it.
+#[derive(Debug, Clone)]
+pub struct Notification {
+    pub query: FinderQuery,
+    pub allocation: Allocation,
+    pub transition: Transition,
+    /// The rank of the alternative of the query's group that was matched before, if this one is better
+    pub better_than: Option<usize>,
+    /// What the allocation clashes with
+    pub clashes: Vec<Clash>,
+}
+
+impl Notification {
+    pub fn title(&self) -> String {
+        match self.transition {
+            Transition::SoldOut => format!("Seats taken for {}", self.query.unit_code),
+            _ if self.better_than.is_some() => format!("Better seats found for {}", self.query.unit_code),
+            _ => format!("Seats found for {}", self.query.unit_code),
+        }
+    }
+
+    pub fn message(&self) -> String {
+        let mut message = format!(
+            "Activity {} of {} {}",
+            self.allocation.activity,
+            self.query.unit_code,
+            self.transition
+        );
+
+        match (&self.query.alternative, self.better_than) {
+            (Some(alternative), Some(better_than)) => {
+                message = format!("{} ({}, better than alternative {})", message, alternative, better_than);
+            }
+            (Some(alternative), None) => message = format!("{} ({})", message, alternative),
+            (None, _) => {}
+        }
+        if !self.clashes.is_empty() {
+            let clashes: Vec<String> = self.clashes.iter().map(Clash::to_string).collect();
+            message = format!("{}; clashes with {}", message, clashes.join(", "));
+        }
+        message
+    }
+}
+
+pub trait Notifier: Send + Sync {
+    fn notify<'a>(&'a self, notification: &'a Notification) -> BoxFuture<'a, Result<()>>;
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(tag = "type", rename_all = "lowercase", deny_unknown_fields)]
+pub enum NotifierConfig {
+    Bell,
+    Desktop,
+    Audio {
+        path: PathBuf,
+        #[serde(default = "default_audio_repeat")]
+        repeat: u32,
+    },
+    Command {
+        program: String,
+        #[serde(default)]
+        args: Vec<String>,
+    },
+    Webhook(WebhookConfig),
+    Email(EmailConfig),
+}
+
+fn default_audio_repeat() -> u32 {
+    DEFAULT_AUDIO_REPEAT
+}
+
+impl fmt::Display for NotifierConfig {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Bell => write!(f, "bell"),
+            Self::Desktop => write!(f, "desktop"),
+            Self::Audio { path, repeat } => write!(f, "audio {} ({} times)", path.display(), repeat),
+            Self::Command { program, args } if args.is_empty() => write!(f, "command {}", program),
+            Self::Command { program, args } => write!(f, "command {} {}", program, args.join(" ")),
+            Self::Webhook(config) => write!(f, "webhook {}", config.url),
+            Self::Email(config) => write!(f, "email to {} via {}", config.to.join(", "), config.host),
+        }
+    }
+}
+
+impl NotifierConfig {
+    pub fn build(&self) -> Arc<dyn Notifier> {
+        match self {
+            Self::Bell => Arc::new(BellNotifier),
+            Self::Desktop => Arc::new(DesktopNotifier),
+            Self::Audio { path, repeat } => Arc::new(AudioNotifier::new(path.clone(), *repeat)),
+            Self::Command { program, args } => Arc::new(CommandNotifier::new(program.clone(), args.clone())),
+            Self::Webhook(config) => Arc::new(WebhookNotifier::new(config.clone())),
+            Self::Email(config) => Arc::new(EmailNotifier::new(config.clone())),
+        }
+    }
+}
+
+/// Sends notifications in the background so that a slow or failing
+/// notifier never holds up the search.
+#[derive(Default)]
+pub struct Notifiers {
+    backends: HashMap<String, Arc<dyn Notifier>>,
+    default: Vec<String>,
+    pending: Mutex<Vec<JoinHandle<()>>>,
+}
+
+impl fmt::Debug for Notifiers {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Notifiers")
+            .field("backends", &self.backends.keys().collect::<Vec<_>>())
+            .field("default", &self.default)
+            .finish_non_exhaustive()
+    }
+}
+
+impl Notifiers {
+    pub fn new(configs: &BTreeMap<String, NotifierConfig>, default: &[String]) -> Self {
+        let backends = configs
+            .iter()
+            .map(|(name, config)| (name.clone(), config.build()))
+            .collect();
+
+        Self { backends, default: default.to_vec(), pending: Mutex::new(Vec::new()) }
+    }
+
+    pub fn dispatch(&self, notification: Notification) {
+        let names = notification.query.notify.as_ref().unwrap_or(&self.default);
+        if names.is_empty() {
+            return;
+        }
+
+        let names = names.clone();
+        let notification = Arc::new(notification);
+        let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+        pending.retain(|handle| !handle.is_finished());
+
+        for name in names.iter() {
+            let Some(notifier) = self.backends.get(name).cloned() else {
+                error!("Notifier {:?} is not defined", name);
+                continue;
+            };
+
+            let name = name.clone();
+            let notification = Arc::clone(&notification);
+            pending.push(tokio::spawn(async move {
+                match notifier.notify(&notification).await {
+                    Ok(()) => info!("Sent notification via {:?}", name),
+                    Err(e) => error!("Error sending notification via {:?}: {}", name, e),
+                }
+            }));
+        }
+    }
+
+    /// Waits for every notification that is still being sent.
+    pub async fn wait(&self) {
+        let pending: Vec<JoinHandle<()>> = {
+            let mut pending = self.pending.lock().unwrap_or_else(|e| e.into_inner());
+            pending.drain(..).collect()
+        };
+
+        for handle in pending {
+            let _ = handle.await;
+        }
+    }
+}
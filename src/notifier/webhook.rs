@@ -0,0 +1,178 @@
+use std::collections::BTreeMap;
+
+use futures::future::{BoxFuture, FutureExt};
+use regex::Captures;
+use reqwest::header::{HeaderMap, HeaderName, HeaderValue};
+use serde::{Deserialize, Deserializer, Serialize};
+use serde::de;
+use serde_json::Value;
+use tokio::time::Duration;
+
+use crate::allocation::Allocation;
+use crate::consts::{PLACEHOLDER_RE, WEBHOOK_TIMEOUT_MS};
+use crate::error::{NotifyError, Result};
+use crate::group::Alternative;
+use crate::planner::Clash;
+use crate::query::FinderQuery;
+use crate::retry::RetryPolicy;
+use super::{Notification, Notifier};
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct WebhookConfig {
+    pub url: String,
+    #[serde(default, deserialize_with = "deserialize_headers")]
+    pub headers: HeaderMap,
+    /// A JSON template whose strings may contain placeholders such as `{unit_code}`.
+    /// The [payload](WebhookPayload) is sent if no template is given.
+    #[serde(default)]
+    pub body: Option<Value>,
+    /// Requests that fail with a 5xx status or do not reach the server are retried
+    #[serde(default)]
+    pub retry: RetryPolicy,
+}
+
+fn deserialize_headers<'de, D: Deserializer<'de>>(deserializer: D) -> Result<HeaderMap, D::Error> {
+    let headers = BTreeMap::<String, String>::deserialize(deserializer)?;
+    let mut map = HeaderMap::with_capacity(headers.len());
+
+    for (name, value) in headers {
+        let name = HeaderName::from_bytes(name.as_bytes())
+            .map_err(|_| de::Error::custom(format!("invalid header name {:?}", name)))?;
+        let value = HeaderValue::from_str(&value)
+            .map_err(|_| de::Error::custom(format!("invalid value for header {:?}", name.as_str())))?;
+        map.insert(name, value);
+    }
+    Ok(map)
+}
+
+/// The JSON document that is POSTed when no body template is configured.
+#[derive(Debug, Serialize)]
+pub struct WebhookPayload<'a> {
+    /// One of `available`, `increased`, `reminder` or `sold_out`
+    pub event: &'static str,
+    pub title: String,
+    pub message: String,
+    pub unit_code: &'a str,
+    pub allocation: &'a Allocation,
+    pub query: &'a FinderQuery,
+    /// The place of the query in its group, if it is an alternative of one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alternative: Option<&'a Alternative>,
+    /// The rank of the alternative that was matched before, if this one is better
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub better_than: Option<usize>,
+    /// What the allocation clashes with
+    #[serde(skip_serializing_if = "<[Clash]>::is_empty")]
+    pub clashes: &'a [Clash],
+}
+
+impl<'a> WebhookPayload<'a> {
+    pub fn new(notification: &'a Notification) -> Self {
+        Self {
+            event: notification.transition.event(),
+            title: notification.title(),
+            message: notification.message(),
+            unit_code: &notification.query.unit_code,
+            allocation: &notification.allocation,
+            query: &notification.query,
+            alternative: notification.query.alternative.as_ref(),
+            better_than: notification.better_than,
+            clashes: &notification.clashes,
+        }
+    }
+
+    /// The values that placeholders in a body template are replaced with: `{event}`, `{title}`,
+    /// `{message}`, `{unit_code}`, every field of the allocation, e.g. `{seats}`, and
+    /// `{alternative_group}` and `{alternative_rank}` for alternatives of a group.
+    pub fn variables(&self) -> BTreeMap<String, String> {
+        let mut variables = BTreeMap::new();
+        variables.insert("event".to_owned(), self.event.to_owned());
+        variables.insert("title".to_owned(), self.title.clone());
+        variables.insert("message".to_owned(), self.message.clone());
+        variables.insert("unit_code".to_owned(), self.unit_code.to_owned());
+        if let Some(alternative) = self.alternative {
+            variables.insert("alternative_group".to_owned(), alternative.group.clone());
+            variables.insert("alternative_rank".to_owned(), alternative.rank.to_string());
+        }
+
+        if let Ok(Value::Object(allocation)) = serde_json::to_value(self.allocation) {
+            for (key, value) in allocation {
+                let value = match value {
+                    Value::String(value) => value,
+                    value => value.to_string(),
+                };
+                variables.insert(key, value);
+            }
+        }
+        variables
+    }
+}
+
+/// Replaces the placeholders in every string of `template`. Values are not searched
+/// for placeholders themselves, and unknown placeholders are left as they are.
+pub fn render(template: &Value, variables: &BTreeMap<String, String>) -> Value {
+    match template {
+        Value::String(template) => {
+            let rendered = PLACEHOLDER_RE.replace_all(template, |caps: &Captures| match variables.get(&caps[1]) {
+                Some(value) => value.clone(),
+                None => caps[0].to_owned(),
+            });
+            Value::String(rendered.into_owned())
+        }
+        Value::Array(values) => Value::Array(
+            values.iter().map(|value| render(value, variables)).collect()
+        ),
+        Value::Object(map) => Value::Object(
+            map.iter().map(|(key, value)| (key.clone(), render(value, variables))).collect()
+        ),
+        value => value.clone(),
+    }
+}
+
+/// POSTs the notification as JSON to a URL.
+pub struct WebhookNotifier {
+    config: WebhookConfig,
+    client: reqwest::Client,
+}
+
+impl WebhookNotifier {
+    pub fn new(config: WebhookConfig) -> Self {
+        Self { config, client: reqwest::Client::new() }
+    }
+
+    pub fn body(&self, notification: &Notification) -> Value {
+        let payload = WebhookPayload::new(notification);
+        match self.config.body {
+            Some(ref template) => render(template, &payload.variables()),
+            None => serde_json::to_value(&payload).unwrap_or_default(),
+        }
+    }
+
+    async fn post(&self, body: &Value) -> Result<(), NotifyError> {
+        let response = self.client
+            .post(&self.config.url)
+            .headers(self.config.headers.clone())
+            .json(body)
+            .timeout(Duration::from_millis(WEBHOOK_TIMEOUT_MS))
+            .send()
+            .await?;
+
+        let status = response.status();
+        if !status.is_success() {
+            return Err(NotifyError::HttpStatus(status.as_u16()));
+        }
+        Ok(())
+    }
+}
+
+impl Notifier for WebhookNotifier {
+    fn notify<'a>(&'a self, notification: &'a Notification) -> BoxFuture<'a, Result<()>> {
+        async move {
+            let body = self.body(notification);
+            let step = format!("sending the webhook to {}", self.config.url);
+            self.config.retry.retry(step, || self.post(&body)).await
+        }
+        .boxed()
+    }
+}
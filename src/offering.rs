@@ -0,0 +1,124 @@
+use std::fmt;
+use std::str::FromStr;
+
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::{self, Unexpected, Visitor};
+
+use crate::consts::SUBCODE_RE;
+use crate::error::ParseError;
+use crate::session::Session;
+
+/// An offering of a unit as listed by the timetable, e.g. `COMP2123-S2C-ND-CC`: the unit code,
+/// the session code, the attendance mode (e.g. `ND` for normal day) and the location
+/// (e.g. `CC` for Camperdown/Darlington).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Offering {
+    subcode: String,
+    unit_code: String,
+    session_code: String,
+    session: Session,
+    attendance: Option<String>,
+    location: Option<String>,
+}
+
+impl Offering {
+    /// The full subcode, e.g. `COMP2123-S2C-ND-CC`.
+    pub fn subcode(&self) -> &str {
+        &self.subcode
+    }
+
+    pub fn unit_code(&self) -> &str {
+        &self.unit_code
+    }
+
+    /// The session code, e.g. `S2C` or `S1CIJA` for an intensive session.
+    pub fn session_code(&self) -> &str {
+        &self.session_code
+    }
+
+    pub fn session(&self) -> &Session {
+        &self.session
+    }
+
+    /// The attendance mode code, e.g. `ND`.
+    pub fn attendance(&self) -> Option<&str> {
+        self.attendance.as_deref()
+    }
+
+    /// The location code, e.g. `CC`.
+    pub fn location(&self) -> Option<&str> {
+        self.location.as_deref()
+    }
+
+    /// Whether the offering is wanted by a query for `session`. A semester also takes in
+    /// the other sessions coded under it, e.g. semester 1 takes in `S1CIJA`.
+    pub fn is_in(&self, session: &Session) -> bool {
+        match session {
+            Session::Semester(semester) => {
+                let prefix = format!("S{}", semester.clone() as u8);
+                self.session_code.len() > prefix.len() && self.session_code.starts_with(&prefix)
+            }
+            session => session.matches(&self.session),
+        }
+    }
+
+    /// Whether `subcode`, e.g. as listed by the timetable, is this offering, ignoring case
+    /// and any text after the subcode.
+    pub fn is(&self, subcode: &str) -> bool {
+        subcode.parse::<Offering>().is_ok_and(|offering| offering.subcode == self.subcode)
+    }
+}
+
+impl FromStr for Offering {
+    type Err = ParseError;
+
+    fn from_str(subcode: &str) -> Result<Self, Self::Err> {
+        let subcode = subcode.trim();
+        let Some(caps) = SUBCODE_RE.captures(subcode) else {
+            return Err(ParseError::RegexNoMatch(SUBCODE_RE.as_str(), subcode.to_string()));
+        };
+        let part = |index: usize| caps.get(index).map(|part| part.as_str().to_uppercase());
+
+        // The timetable may list text after the subcode, which is not part of it
+        Ok(Self {
+            subcode: caps[0].trim_end().to_uppercase(),
+            unit_code: caps[1].to_uppercase(),
+            session_code: caps[2].to_uppercase(),
+            session: Session::from_code(&caps[2]),
+            attendance: part(3),
+            location: part(4),
+        })
+    }
+}
+
+impl fmt::Display for Offering {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.subcode)
+    }
+}
+
+impl<'de> Deserialize<'de> for Offering {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct OfferingVisitor;
+
+        impl<'de> Visitor<'de> for OfferingVisitor {
+            type Value = Offering;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("an offering subcode such as \"COMP2123-S2C-ND-CC\"")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                value.parse().map_err(|_| E::invalid_value(Unexpected::Str(value), &self))
+            }
+        }
+
+        deserializer.deserialize_str(OfferingVisitor)
+    }
+}
+
+impl Serialize for Offering {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.subcode)
+    }
+}
@@ -0,0 +1,115 @@
+use colored::Colorize;
+use strum::Display;
+
+use crate::allocation::Allocation;
+use crate::error::Error;
+use crate::query::FinderQuery;
+
+#[derive(Debug, Display, Clone, Copy, PartialEq)]
+pub enum FindStep {
+    #[strum(to_string = "searching for units")]
+    LocateInteractees,
+    #[strum(to_string = "toggling the advanced filter")]
+    ToggleAdvancedFilter,
+    #[strum(to_string = "searching the timetable")]
+    SearchTimetable,
+    #[strum(to_string = "selecting the unit offering")]
+    SelectUnit,
+    #[strum(to_string = "searching for the query")]
+    SearchQuery,
+    #[strum(to_string = "clearing the timetable")]
+    ResetTimetable,
+}
+
+/// The result of searching the timetable for a single query. Matching
+/// allocations are ranked from most to least preferred.
+#[derive(Debug)]
+pub enum QueryOutcome {
+    /// At least one matching allocation has as many seats left as the query needs
+    Found(Vec<Allocation>),
+    NoSeats(Vec<Allocation>),
+    NotFound,
+    Error { step: FindStep, cause: Error },
+}
+
+impl QueryOutcome {
+    pub fn allocations(&self) -> &[Allocation] {
+        match self {
+            Self::Found(allocations) | Self::NoSeats(allocations) => allocations,
+            Self::NotFound | Self::Error { .. } => &[],
+        }
+    }
+
+    pub fn is_error(&self) -> bool {
+        matches!(self, Self::Error { .. })
+    }
+
+    /// Combines the outcome with the result of resetting the timetable afterwards, returning
+    /// the reset's error if the page was left in a state the next search cannot start from.
+    /// Scraped allocations are kept even if the reset failed.
+    pub fn after_reset(self, reset: Result<(), Error>) -> (Self, Option<Error>) {
+        match (self, reset) {
+            (outcome, Ok(())) => (outcome, None),
+            // The timetable may be left in any state after an error, so failing
+            // to reset it is expected and the original cause is kept
+            (outcome @ Self::Error { .. }, Err(_)) => (outcome, None),
+            (outcome, Err(e)) => (outcome, Some(e)),
+        }
+    }
+
+    /// A one line description of the outcome of searching for `query`.
+    pub fn summary(&self, query: &FinderQuery) -> String {
+        match self {
+            Self::Found(allocations) => {
+                let available: Vec<String> = allocations
+                    .iter()
+                    .filter(|allocation| query.has_seats(allocation))
+                    .map(|allocation| format!("activity {} ({} seats left)", allocation.activity, allocation.seats))
+                    .collect();
+                format!("found {}", available.join(", "))
+            }
+            Self::NoSeats(_) => "no seats left".to_owned(),
+            Self::NotFound => "no matching allocation".to_owned(),
+            Self::Error { step, cause } => format!("error {}: {}", step, cause),
+        }
+    }
+}
+
+pub fn print_summary(outcomes: &[(&FinderQuery, QueryOutcome)]) {
+    let mut found = 0;
+    let mut no_seats = 0;
+    let mut not_found = 0;
+    let mut errors = 0;
+
+    for (query, outcome) in outcomes.iter() {
+        let line = format!("{}: {}", query, outcome.summary(query));
+        match outcome {
+            QueryOutcome::Found(_) => {
+                found += 1;
+                println!("{}", line.green());
+            }
+            QueryOutcome::NoSeats(_) => {
+                no_seats += 1;
+                println!("{}", line.yellow());
+            }
+            QueryOutcome::NotFound => {
+                not_found += 1;
+                println!("{}", line.yellow());
+            }
+            QueryOutcome::Error { .. } => {
+                errors += 1;
+                println!("{}", line.red());
+            }
+        }
+    }
+
+    let summary = format!(
+        "{} queries: {} found, {} without seats, {} not found, {} failed",
+        outcomes.len(),
+        found,
+        no_seats,
+        not_found,
+        errors
+    );
+    println!("{}", summary.bold());
+}
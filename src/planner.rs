@@ -0,0 +1,185 @@
+use std::collections::HashMap;
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::allocation::{Allocation, Day, TwentyFourHourTime, WeekSet};
+use crate::query::FinderQuery;
+
+/// Fixed commitments that found allocations must fit around.
+#[derive(Debug, Clone, PartialEq, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PlannerConfig {
+    pub commitments: Vec<Commitment>,
+    /// Do not notify about allocations that clash with a commitment or with
+    /// the allocation found for an earlier query
+    pub suppress_clashes: bool,
+}
+
+/// A weekly time that is already taken, e.g. work or a unit that is already enrolled in.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct Commitment {
+    pub name: String,
+    pub day: Day,
+    pub start: TwentyFourHourTime,
+    pub end: TwentyFourHourTime,
+    /// The teaching weeks of the commitment, or every week if not given
+    #[serde(default)]
+    pub weeks: Option<WeekSet>,
+}
+
+impl Commitment {
+    pub fn slot(&self) -> Slot {
+        Slot { day: self.day, start: self.start, end: self.end, weeks: self.weeks.clone() }
+    }
+}
+
+/// A weekly period of time.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Slot {
+    pub day: Day,
+    pub start: TwentyFourHourTime,
+    pub end: TwentyFourHourTime,
+    /// `None` if the slot is in every week
+    pub weeks: Option<WeekSet>,
+}
+
+impl Slot {
+    /// The time taken by an allocation, if its duration could be understood.
+    pub fn from_allocation(allocation: &Allocation) -> Option<Self> {
+        Some(Self {
+            day: allocation.day,
+            start: allocation.time,
+            end: allocation.end_time()?,
+            weeks: allocation.weeks.weeks().cloned(),
+        })
+    }
+
+    pub fn overlaps(&self, other: &Slot) -> bool {
+        let weeks_overlap = match (&self.weeks, &other.weeks) {
+            (Some(weeks), Some(other)) => weeks.intersects(other),
+            _ => true,
+        };
+        self.day == other.day && self.start < other.end && other.start < self.end && weeks_overlap
+    }
+}
+
+impl fmt::Display for Slot {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let day: &str = self.day.into();
+        write!(f, "{} {}-{}", day, self.start, self.end)
+    }
+}
+
+/// What an allocation clashes with.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+#[serde(tag = "with", rename_all = "snake_case")]
+pub enum Clash {
+    Commitment { name: String, slot: String },
+    Allocation { unit_code: String, activity: u64, slot: String, earlier: bool },
+}
+
+impl Clash {
+    /// Whether the clash stops the allocation from being notified about when
+    /// `suppress_clashes` is set.
+    pub fn suppresses(&self) -> bool {
+        match self {
+            Self::Commitment { .. } => true,
+            Self::Allocation { earlier, .. } => *earlier,
+        }
+    }
+}
+
+impl fmt::Display for Clash {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Commitment { name, slot } => write!(f, "{} ({})", name, slot),
+            Self::Allocation { unit_code, activity, slot, .. } => {
+                write!(f, "activity {} of {} ({})", activity, unit_code, slot)
+            }
+        }
+    }
+}
+
+/// Checks the allocations found for every query against each other and
+/// against the commitments.
+#[derive(Debug, Default)]
+pub struct Planner {
+    config: PlannerConfig,
+    /// The most preferred allocation with seats for each query, by the query's index
+    chosen: HashMap<usize, Allocation>,
+}
+
+impl Planner {
+    pub fn new(config: PlannerConfig) -> Self {
+        Self { config, chosen: HashMap::new() }
+    }
+
+    pub fn config(&self) -> &PlannerConfig {
+        &self.config
+    }
+
+    /// Applies a new config, forgetting the allocations found since the queries may have changed.
+    pub fn reload(&mut self, config: PlannerConfig) {
+        self.config = config;
+        self.chosen.clear();
+    }
+
+    /// The chosen allocation of every query that has one, by the query's index.
+    pub fn chosen(&self) -> Vec<(usize, &Allocation)> {
+        let mut chosen: Vec<(usize, &Allocation)> = self.chosen
+            .iter()
+            .map(|(&index, allocation)| (index, allocation))
+            .collect();
+        chosen.sort_by_key(|&(index, _)| index);
+        chosen
+    }
+
+    /// Records the allocations found for the query at `index`, choosing the most preferred one with seats.
+    pub fn update(&mut self, index: usize, query: &FinderQuery, allocations: &[Allocation]) {
+        match allocations.iter().find(|allocation| query.has_seats(allocation)) {
+            Some(allocation) => self.chosen.insert(index, allocation.clone()),
+            None => self.chosen.remove(&index),
+        };
+    }
+
+    /// What `allocation`, found for the query at `index`, clashes with. Allocations found
+    /// for alternatives of the same group are never taken together, so they cannot clash.
+    pub fn clashes(&self, index: usize, queries: &[FinderQuery], allocation: &Allocation) -> Vec<Clash> {
+        let Some(slot) = Slot::from_allocation(allocation) else {
+            return Vec::new();
+        };
+        let group = queries[index].alternative.as_ref().map(|alternative| &alternative.group);
+
+        let commitments = self.config.commitments
+            .iter()
+            .filter(|commitment| commitment.slot().overlaps(&slot))
+            .map(|commitment| Clash::Commitment { name: commitment.name.clone(), slot: commitment.slot().to_string() });
+
+        let allocations = self.chosen()
+            .into_iter()
+            .filter(|&(other, _)| other != index)
+            .filter(|&(other, _)| {
+                let other_group = queries.get(other).and_then(|query| query.alternative.as_ref());
+                group.is_none() || other_group.map(|alternative| &alternative.group) != group
+            })
+            .filter_map(|(other, other_allocation)| {
+                let unit_code = queries.get(other)?.unit_code();
+                let other_slot = Slot::from_allocation(other_allocation)?;
+                other_slot.overlaps(&slot).then(|| Clash::Allocation {
+                    unit_code,
+                    activity: other_allocation.activity,
+                    slot: other_slot.to_string(),
+                    earlier: other < index,
+                })
+            });
+
+        commitments.chain(allocations).collect()
+    }
+
+    /// Whether an allocation with these clashes should not be notified about.
+    pub fn suppresses(&self, clashes: &[Clash]) -> bool {
+        self.config.suppress_clashes && clashes.iter().any(Clash::suppresses)
+    }
+}
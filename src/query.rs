@@ -1,144 +1,538 @@
-use std::path::PathBuf;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
 
-use serde_json::{self, Value};
+use clap::ValueEnum;
+use regex::Regex;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::{self, MapAccess, SeqAccess, Unexpected, Visitor};
+use serde::ser::SerializeMap;
+use serde_json::Value;
 
-use crate::allocation::{ActivityType, Day, Semester, TwentyFourHourTime};
+use crate::allocation::{ActivityType, Allocation, Day, TwentyFourHourTime, WeekSet};
 use crate::consts::{
-    DEFAULT_HEADLESS, 
     DEFAULT_PORT, 
-    DEFAULT_RUN_CHROMEDRIVER, 
-    MIN_PORT, 
-    MAX_PORT, 
     PUBLIC_TIMETABLE_EVEN, 
     PUBLIC_TIMETABLE_ODD, 
-    SUBCODE_RE, 
     UNIT_CODE_RE
 };
-use crate::error::ParseError;
+use crate::error::{Error, ParseError};
+use crate::group::Alternative;
+use crate::offering::Offering;
+use crate::session::Session;
+use crate::notifier::NotifierConfig;
+use crate::retry::RetryConfig;
+use crate::tracker::AlertConfig;
+use crate::history::HistoryConfig;
+use crate::planner::PlannerConfig;
+use crate::calendar::CalendarConfig;
+use crate::schedule::{deserialize_optional_interval, ScheduleConfig};
 use crate::methods::{
     port_is_occupied, 
     public_timetable_url_default, 
     unoccupied_port
 };
 
-const HEADLESS: &str = "headless";
-const PORT: &str = "port";
-const PARITY: &str = "parity";
-const RUN_CHROMEDRIVER: &str = "run_chromedriver";
-const MUSIC: &str = "music";
-
-const UNIT_CODE: &str = "unit_code";
-const SEMESTER: &str = "semester";
-const DAY: &str = "day";
-const START_AFTER: &str = "start_after";
-const START: &str = "start";
-const ACTIVITIY_TYPE: &str = "activity_type";
-const ACTIVITY: &str = "activity";
-
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
 pub struct FinderQuery {
+    #[serde(deserialize_with = "deserialize_unit_code")]
     pub unit_code: String,
-    pub semester: Semester,
-    pub day: Day,
+    /// The session of the offering to search, e.g. `2`, `"S1CIJA"` or `"summer"`
+    #[serde(default, rename = "semester", alias = "session")]
+    pub session: Session,
+    /// The exact offering to search, which takes precedence over `semester`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subcode: Option<Offering>,
+    /// The days the activity may be on
+    #[serde(
+        rename = "day", 
+        alias = "days", 
+        deserialize_with = "deserialize_days", 
+        serialize_with = "serialize_days"
+    )]
+    pub days: Vec<Day>,
     pub activity_type: ActivityType,
-    pub activity: u64,
+    /// The activity numbers to accept, in order of preference
+    #[serde(default)]
+    pub activity: ActivitySelector,
+    #[serde(default, alias = "start", skip_serializing_if = "Option::is_none")]
     pub start_after: Option<TwentyFourHourTime>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub start_before: Option<TwentyFourHourTime>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub end_before: Option<TwentyFourHourTime>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub campus: Option<TextFilter>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub location: Option<TextFilter>,
+    /// The fewest seats that count as seats being available
+    #[serde(default, deserialize_with = "deserialize_min_seats", skip_serializing_if = "Option::is_none")]
+    pub min_seats: Option<i16>,
+    /// Weeks that the activity must run in
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub weeks: Option<WeekSet>,
+    /// Seconds between searches for this query instead of `schedule.interval`
+    #[serde(
+        default, 
+        deserialize_with = "deserialize_optional_interval", 
+        skip_serializing_if = "Option::is_none"
+    )]
+    pub interval: Option<u64>,
+    /// The notifiers to use for this query instead of the top-level `notify`
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub notify: Option<Vec<String>>,
+    /// The place of the query in its group, if it is an alternative of one
+    #[serde(skip)]
+    pub alternative: Option<Alternative>,
 }
 
 impl FinderQuery {
     pub fn try_new(config: &Value) -> Result<Self, ParseError> {
-        let unit_code = config[UNIT_CODE]
-            .as_str()
-            .ok_or(ParseError::ParseJsonError)?
-            .to_string();
-        if !UNIT_CODE_RE.is_match(unit_code.as_str()) {
-            return Err(ParseError::RegexNoMatch(SUBCODE_RE.as_str(), unit_code));
-        }
-        let day = match config[DAY].as_u64() {
-            Some(value) => Day::try_from(value)?,
-            None => match config[DAY].as_str() {
-                Some(value) => Day::try_from(value)?,
-                None => return Err(ParseError::ParseJsonError),
+        serde_path_to_error::deserialize(config).map_err(ParseError::from)
+    }
+
+    pub fn unit_code(&self) -> String {
+        self.unit_code.clone()
+    }
+
+    /// Whether `allocation` fits every constraint of the query, regardless of its seats.
+    /// An allocation whose duration or weeks cannot be understood never fits a query
+    /// that constrains them.
+    pub fn matches(&self, allocation: &Allocation) -> bool {
+        allocation.activity_type == self.activity_type
+            && self.days.contains(&allocation.day)
+            && self.activity.matches(allocation.activity)
+            && self.start_after.is_none_or(|start_after| allocation.time >= start_after)
+            && self.start_before.is_none_or(|start_before| allocation.time <= start_before)
+            && self.end_before.is_none_or(|end_before| {
+                allocation.end_time().is_some_and(|end| end <= end_before)
+            })
+            && self.campus.as_ref().is_none_or(|campus| campus.matches(&allocation.campus))
+            && self.location.as_ref().is_none_or(|location| location.matches(&allocation.location))
+            && self.weeks.as_ref().is_none_or(|weeks| {
+                weeks.iter().all(|week| allocation.runs_in_week(week) == Some(true))
+            })
+    }
+
+    /// Whether `allocation` has at least `min_seats` (or any) seats left.
+    pub fn has_seats(&self, allocation: &Allocation) -> bool {
+        allocation.seats >= self.min_seats.unwrap_or(1)
+    }
+}
+
+impl fmt::Display for FinderQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} {}", self.unit_code, self.activity_type.checkbox_id_suffix())?;
+        if self.activity != ActivitySelector::Any {
+            write!(f, " {}", self.activity)?;
+        }
+        let days: Vec<&str> = self.days.iter().map(|&day| day.into()).collect();
+        write!(f, " on {}", days.join(", "))?;
+
+        if let Some(ref start_after) = self.start_after {
+            write!(f, " after {}", start_after)?;
+        }
+        if let Some(ref start_before) = self.start_before {
+            write!(f, " before {}", start_before)?;
+        }
+        if let Some(ref end_before) = self.end_before {
+            write!(f, " ending by {}", end_before)?;
+        }
+        Ok(())
+    }
+}
+
+/// The activity numbers that a query accepts.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum ActivitySelector {
+    #[default]
+    Any,
+    One(u64),
+    /// Activities in order of preference
+    List(Vec<u64>),
+    /// An inclusive range of activities
+    Range { from: u64, to: u64 },
+}
+
+impl ActivitySelector {
+    pub fn matches(&self, activity: u64) -> bool {
+        match self {
+            Self::Any => true,
+            Self::One(number) => *number == activity,
+            Self::List(numbers) => numbers.contains(&activity),
+            Self::Range { from, to } => (*from..=*to).contains(&activity),
+        }
+    }
+
+    /// How preferred `activity` is, where lower is better. Activities in a range
+    /// (or any activity) are equally preferred.
+    pub fn preference(&self, activity: u64) -> usize {
+        match self {
+            Self::List(numbers) => numbers
+                .iter()
+                .position(|&number| number == activity)
+                .unwrap_or(numbers.len()),
+            _ => 0,
+        }
+    }
+}
+
+impl fmt::Display for ActivitySelector {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Any => write!(f, "any"),
+            Self::One(number) => write!(f, "{}", number),
+            Self::List(numbers) => {
+                let numbers: Vec<String> = numbers.iter().map(u64::to_string).collect();
+                write!(f, "{}", numbers.join(", "))
             }
-        };
+            Self::Range { from, to } => write!(f, "{}-{}", from, to),
+        }
+    }
+}
 
-        let semester = match config[SEMESTER].as_u64() {
-            Some(semester) => Semester::try_from(semester)?,
-            None => Semester::Any,
-        };
+impl FromStr for ActivitySelector {
+    type Err = ParseError;
 
-        let activity_type = ActivityType::try_from(
-            config[ACTIVITIY_TYPE].as_str().ok_or(ParseError::ParseJsonError)?
-        )?;
-        let activity = config[ACTIVITY].as_u64().ok_or(ParseError::ParseJsonError)?;
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim();
+        if value.eq_ignore_ascii_case("any") {
+            return Ok(Self::Any);
+        }
 
-        let start_after = match config[START_AFTER].as_str() {
-            Some(value) => TwentyFourHourTime::new(value),
-            None => config[START].as_str().and_then(TwentyFourHourTime::new),
+        let parse = |number: &str| {
+            number
+                .trim()
+                .parse::<u64>()
+                .map_err(|_| ParseError::ParseActivityError(value.to_owned()))
         };
+        match value.split_once('-') {
+            Some((from, to)) => {
+                let (from, to) = (parse(from)?, parse(to)?);
+                if from > to {
+                    return Err(ParseError::ParseActivityError(value.to_owned()));
+                }
+                Ok(Self::Range { from, to })
+            }
+            None => parse(value).map(Self::One),
+        }
+    }
+}
 
-        Ok(FinderQuery { 
-            unit_code, 
-            day, 
-            semester, 
-            activity_type, 
-            activity,
-            start_after
-        })
+impl<'de> Deserialize<'de> for ActivitySelector {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct ActivitySelectorVisitor;
+
+        impl<'de> Visitor<'de> for ActivitySelectorVisitor {
+            type Value = ActivitySelector;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("an activity number, a list of activity numbers, a range such as \"3-6\" or \"any\"")
+            }
+
+            fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+                Ok(ActivitySelector::One(value))
+            }
+
+            fn visit_i64<E: de::Error>(self, value: i64) -> Result<Self::Value, E> {
+                match u64::try_from(value) {
+                    Ok(value) => self.visit_u64(value),
+                    Err(_) => Err(E::invalid_value(Unexpected::Signed(value), &self)),
+                }
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                value.parse().map_err(|_| E::invalid_value(Unexpected::Str(value), &self))
+            }
+
+            fn visit_unit<E: de::Error>(self) -> Result<Self::Value, E> {
+                Ok(ActivitySelector::Any)
+            }
+
+            fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+                let mut numbers = Vec::new();
+                while let Some(number) = seq.next_element::<u64>()? {
+                    numbers.push(number);
+                }
+
+                match numbers.as_slice() {
+                    [] => Err(de::Error::invalid_length(0, &self)),
+                    [number] => Ok(ActivitySelector::One(*number)),
+                    _ => Ok(ActivitySelector::List(numbers)),
+                }
+            }
+        }
+
+        deserializer.deserialize_any(ActivitySelectorVisitor)
     }
+}
 
-    pub fn unit_code(&self) -> String {
-        self.unit_code.clone()
+impl Serialize for ActivitySelector {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::One(number) => serializer.serialize_u64(*number),
+            Self::List(numbers) => numbers.serialize(serializer),
+            _ => serializer.collect_str(self),
+        }
+    }
+}
+
+/// Text that a field of an allocation must contain (ignoring case) or a regex it must match.
+#[derive(Debug, Clone)]
+pub enum TextFilter {
+    Contains(String),
+    Regex(Regex),
+}
+
+impl TextFilter {
+    pub fn matches(&self, text: &str) -> bool {
+        match self {
+            Self::Contains(needle) => text.to_lowercase().contains(&needle.to_lowercase()),
+            Self::Regex(regex) => regex.is_match(text),
+        }
+    }
+}
+
+impl PartialEq for TextFilter {
+    fn eq(&self, other: &Self) -> bool {
+        match (self, other) {
+            (Self::Contains(a), Self::Contains(b)) => a == b,
+            (Self::Regex(a), Self::Regex(b)) => a.as_str() == b.as_str(),
+            _ => false,
+        }
+    }
+}
+
+impl fmt::Display for TextFilter {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Contains(needle) => write!(f, "contains {:?}", needle),
+            Self::Regex(regex) => write!(f, "matches /{}/", regex.as_str()),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for TextFilter {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        struct TextFilterVisitor;
+
+        impl<'de> Visitor<'de> for TextFilterVisitor {
+            type Value = TextFilter;
+
+            fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+                f.write_str("text to search for or a map such as { \"regex\": \"^Carslaw\" }")
+            }
+
+            fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+                Ok(TextFilter::Contains(value.to_owned()))
+            }
+
+            fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+                let Some(key) = map.next_key::<String>()? else {
+                    return Err(de::Error::missing_field("regex"));
+                };
+                if key != "regex" {
+                    return Err(de::Error::unknown_field(&key, &["regex"]));
+                }
+
+                let pattern = map.next_value::<String>()?;
+                if let Some(key) = map.next_key::<String>()? {
+                    return Err(de::Error::unknown_field(&key, &["regex"]));
+                }
+                Regex::new(&pattern)
+                    .map(TextFilter::Regex)
+                    .map_err(|e| de::Error::custom(format!("invalid regex {:?}: {}", pattern, e)))
+            }
+        }
+
+        deserializer.deserialize_any(TextFilterVisitor)
     }
 }
 
+impl Serialize for TextFilter {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Contains(needle) => serializer.serialize_str(needle),
+            Self::Regex(regex) => {
+                let mut map = serializer.serialize_map(Some(1))?;
+                map.serialize_entry("regex", regex.as_str())?;
+                map.end()
+            }
+        }
+    }
+}
+
+fn deserialize_days<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<Day>, D::Error> {
+    struct DaysVisitor;
+
+    impl<'de> Visitor<'de> for DaysVisitor {
+        type Value = Vec<Day>;
+
+        fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+            f.write_str("a day or a list of days")
+        }
+
+        fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+            Day::deserialize(de::value::U64Deserializer::new(value)).map(|day| vec![day])
+        }
+
+        fn visit_i64<E: de::Error>(self, value: i64) -> Result<Self::Value, E> {
+            Day::deserialize(de::value::I64Deserializer::new(value)).map(|day| vec![day])
+        }
+
+        fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+            Day::deserialize(de::value::StrDeserializer::new(value)).map(|day| vec![day])
+        }
+
+        fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+            let mut days = Vec::new();
+            while let Some(day) = seq.next_element::<Day>()? {
+                if !days.contains(&day) {
+                    days.push(day);
+                }
+            }
+
+            if days.is_empty() {
+                return Err(de::Error::invalid_length(0, &self));
+            }
+            Ok(days)
+        }
+    }
+
+    deserializer.deserialize_any(DaysVisitor)
+}
+
+fn serialize_days<S: Serializer>(days: &[Day], serializer: S) -> Result<S::Ok, S::Error> {
+    match days {
+        [day] => day.serialize(serializer),
+        _ => days.serialize(serializer),
+    }
+}
+
+fn deserialize_min_seats<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<i16>, D::Error> {
+    let min_seats = i16::deserialize(deserializer)?;
+    if min_seats < 1 {
+        return Err(de::Error::invalid_value(
+            Unexpected::Signed(min_seats as i64), 
+            &"at least 1 seat"
+        ));
+    }
+    Ok(Some(min_seats))
+}
+
+fn deserialize_unit_code<'de, D: Deserializer<'de>>(deserializer: D) -> Result<String, D::Error> {
+    let unit_code = String::deserialize(deserializer)?;
+    if !UNIT_CODE_RE.is_match(&unit_code) {
+        return Err(de::Error::invalid_value(
+            Unexpected::Str(&unit_code), 
+            &"a unit code such as \"COMP2123\""
+        ));
+    }
+    Ok(unit_code)
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Deserialize, ValueEnum)]
+#[serde(rename_all = "lowercase")]
+pub enum Parity {
+    Even,
+    Odd,
+}
+
+impl Parity {
+    pub fn public_timetable_url(parity: Option<Parity>) -> &'static str {
+        match parity {
+            Some(Parity::Odd) => PUBLIC_TIMETABLE_ODD,
+            Some(Parity::Even) => PUBLIC_TIMETABLE_EVEN,
+            None => public_timetable_url_default(),
+        }
+    }
+}
+
+#[derive(Debug, Default, Clone)]
+pub struct ConfigOverrides {
+    pub headless: Option<bool>,
+    pub port: Option<u16>,
+    pub parity: Option<Parity>,
+    pub interval: Option<u64>,
+}
+
 #[derive(Debug)]
 pub struct FinderConfig {
     pub port: u16,
     pub public_timetable_url: String,
     pub headless: bool,
     pub run_chromedriver: bool,
-    pub music: Option<PathBuf>,
+    pub retry: RetryConfig,
+    pub notifiers: BTreeMap<String, NotifierConfig>,
+    /// The notifiers used by queries that do not list their own
+    pub notify: Vec<String>,
+    pub alerts: AlertConfig,
+    pub history: HistoryConfig,
+    pub schedule: ScheduleConfig,
+    pub planner: PlannerConfig,
+    pub calendar: Option<CalendarConfig>,
 }
 
 impl FinderConfig {
-    pub fn try_new(json_config: Value) -> Result<Self, ParseError> {
-        let headless = match json_config.get(HEADLESS) {
-            Some(value) => value.as_bool().ok_or(ParseError::ParseJsonError)?,
-            None => DEFAULT_HEADLESS,
+    pub fn new(
+        port: u16, 
+        parity: Option<Parity>, 
+        headless: bool, 
+        run_chromedriver: bool, 
+        retry: RetryConfig
+    ) -> Result<Self, Error> {
+        let port = if run_chromedriver && port_is_occupied(port) {
+            unoccupied_port(DEFAULT_PORT).ok_or(Error::NoAvailablePort)?
+        } else {
+            port
         };
+        let public_timetable_url = Parity::public_timetable_url(parity).to_owned();
 
-        let run_chromedriver = match json_config.get(RUN_CHROMEDRIVER) {
-            Some(value) => value.as_bool().ok_or(ParseError::ParseJsonError)?,
-            None => DEFAULT_RUN_CHROMEDRIVER,
-        };
+        Ok(Self { 
+            port, 
+            public_timetable_url, 
+            headless, 
+            run_chromedriver, 
+            retry, 
+            notifiers: BTreeMap::new(), 
+            notify: Vec::new(), 
+            alerts: AlertConfig::default(), 
+            history: HistoryConfig::default(), 
+            schedule: ScheduleConfig::default(), 
+            planner: PlannerConfig::default(), 
+            calendar: None 
+        })
+    }
 
-        let music = json_config
-            .get(MUSIC)
-            .and_then(|value| value.as_str())
-            .map(PathBuf::from);
+    pub fn with_notifiers(mut self, notifiers: BTreeMap<String, NotifierConfig>, notify: Vec<String>) -> Self {
+        self.notifiers = notifiers;
+        self.notify = notify;
+        self
+    }
 
-        let mut port = match json_config[PORT].as_u64() {
-            Some(port) => port as u16,
-            None => DEFAULT_PORT,
-        };
+    pub fn with_alerts(mut self, alerts: AlertConfig) -> Self {
+        self.alerts = alerts;
+        self
+    }
 
-        if !(MIN_PORT..=MAX_PORT).contains(&port) {
-            return Err(ParseError::ParseJsonError);
-        }
+    pub fn with_history(mut self, history: HistoryConfig) -> Self {
+        self.history = history;
+        self
+    }
 
-        if run_chromedriver && port_is_occupied(port) {
-            port = unoccupied_port(DEFAULT_PORT);
-        }
+    pub fn with_schedule(mut self, schedule: ScheduleConfig) -> Self {
+        self.schedule = schedule;
+        self
+    }
 
-        let parity = json_config[PARITY].as_str().unwrap_or("default");
-        let public_timetable_url = match parity {
-            "odd" => PUBLIC_TIMETABLE_ODD.to_owned(),
-            "even" => PUBLIC_TIMETABLE_EVEN.to_owned(),
-            "default" => public_timetable_url_default().to_owned(),
-            _ => return Err(ParseError::ParseParityError),
-        };
+    pub fn with_planner(mut self, planner: PlannerConfig) -> Self {
+        self.planner = planner;
+        self
+    }
 
-        Ok(Self { port, public_timetable_url, headless, run_chromedriver, music })
+    pub fn with_calendar(mut self, calendar: Option<CalendarConfig>) -> Self {
+        self.calendar = calendar;
+        self
     }
-}
\ No newline at end of file
+}
@@ -0,0 +1,108 @@
+use std::ffi::OsString;
+use std::path::{Path, PathBuf};
+
+use log::info;
+use notify::{Event, EventKind, RecommendedWatcher, RecursiveMode, Watcher};
+use tokio::sync::mpsc::{self, UnboundedReceiver};
+use tokio::time::{self, Duration};
+
+use crate::query::FinderQuery;
+
+const DEBOUNCE: Duration = Duration::from_millis(250);
+
+/// Watches the directory containing the config file rather than the file itself,
+/// since editors commonly save by replacing the file.
+pub struct ConfigWatcher {
+    _watcher: RecommendedWatcher,
+    events: UnboundedReceiver<()>,
+}
+
+impl ConfigWatcher {
+    pub fn new(config_path: &Path) -> notify::Result<Self> {
+        let file_name = config_path.file_name().map(OsString::from);
+        let directory = match config_path.parent() {
+            Some(parent) if !parent.as_os_str().is_empty() => parent.to_path_buf(),
+            _ => PathBuf::from("."),
+        };
+
+        let (sender, events) = mpsc::unbounded_channel();
+        let mut watcher = notify::recommended_watcher(move |event: notify::Result<Event>| {
+            let Ok(event) = event else { return };
+            if matches!(event.kind, EventKind::Access(_)) {
+                return;
+            }
+
+            let touches_config = event
+                .paths
+                .iter()
+                .any(|path| path.file_name() == file_name.as_deref());
+            if touches_config {
+                let _ = sender.send(());
+            }
+        })?;
+        watcher.watch(&directory, RecursiveMode::NonRecursive)?;
+
+        Ok(Self { _watcher: watcher, events })
+    }
+
+    /// Waits until the config file changes. A single save usually produces
+    /// several events, so events arriving shortly after the first are discarded.
+    pub async fn changed(&mut self) {
+        if self.events.recv().await.is_none() {
+            return std::future::pending().await;
+        }
+
+        time::sleep(DEBOUNCE).await;
+        while self.events.try_recv().is_ok() {}
+    }
+}
+
+#[derive(Debug, Default)]
+pub struct QueryDiff<'a> {
+    pub added: Vec<&'a FinderQuery>,
+    pub removed: Vec<&'a FinderQuery>,
+    pub changed: Vec<(&'a FinderQuery, &'a FinderQuery)>,
+}
+
+impl<'a> QueryDiff<'a> {
+    pub fn new(old: &'a [FinderQuery], new: &'a [FinderQuery]) -> Self {
+        let removed: Vec<usize> = (0..old.len()).filter(|&index| !new.contains(&old[index])).collect();
+        let mut added: Vec<usize> = (0..new.len()).filter(|&index| !old.contains(&new[index])).collect();
+
+        // A query that was removed and one that was added at the same position in the
+        // config are reported as a single change, since several queries may share a unit
+        let mut diff = Self::default();
+        for index in removed {
+            match added.iter().position(|&after| after == index) {
+                Some(position) => diff.changed.push((&old[index], &new[added.remove(position)])),
+                None => diff.removed.push(&old[index]),
+            }
+        }
+        diff.added = added.into_iter().map(|index| &new[index]).collect();
+
+        diff
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+
+    pub fn log(&self) {
+        if self.is_empty() {
+            info!("Queries are unchanged.");
+            return;
+        }
+
+        for query in self.added.iter() {
+            info!("Added query: {}", query);
+        }
+
+        for query in self.removed.iter() {
+            info!("Removed query: {}", query);
+        }
+
+        for (before, after) in self.changed.iter() {
+            info!("Changed query: {} -> {}", before, after);
+        }
+    }
+}
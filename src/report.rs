@@ -0,0 +1,289 @@
+use std::fmt;
+use std::path::PathBuf;
+
+use chrono::{DateTime, Duration, Local, Timelike};
+use clap::ValueEnum;
+use colored::Colorize;
+use serde::Serialize;
+use strum::Display;
+
+use crate::allocation::ActivityType;
+use crate::consts::REPORT_TREND_WINDOW;
+use crate::history::{AllocationKey, Observation};
+use crate::session::Session;
+
+#[derive(Debug, Clone, Copy, PartialEq, ValueEnum)]
+pub enum ReportFormat {
+    Table,
+    Csv,
+    Json,
+}
+
+#[derive(Debug, Clone)]
+pub struct ReportOptions {
+    pub format: ReportFormat,
+    /// Only report on this unit
+    pub unit_code: Option<String>,
+    /// Only use observations from the last this many days
+    pub since: Option<u64>,
+    /// The history file to read instead of the one in the config
+    pub history: Option<PathBuf>,
+}
+
+impl ReportOptions {
+    /// Whether `observation` should be included in the report.
+    pub fn includes(&self, observation: &Observation) -> bool {
+        let unit_matches = self.unit_code
+            .as_ref()
+            .is_none_or(|unit_code| unit_code.eq_ignore_ascii_case(&observation.unit_code));
+        let recent = self.since
+            .is_none_or(|days| observation.timestamp >= Local::now() - Duration::days(days as i64));
+        unit_matches && recent
+    }
+}
+
+#[derive(Debug, Display, Clone, Copy, PartialEq, Serialize)]
+#[serde(rename_all = "lowercase")]
+#[strum(serialize_all = "lowercase")]
+pub enum Trend {
+    Rising,
+    Falling,
+    Steady,
+}
+
+/// How the seats of a single allocation behaved over its recorded history.
+#[derive(Debug, Clone, Serialize)]
+pub struct AllocationReport {
+    pub unit_code: String,
+    pub semester: Session,
+    pub activity_type: ActivityType,
+    pub activity: u64,
+    pub observations: usize,
+    pub first_seen: DateTime<Local>,
+    pub last_seen: DateTime<Local>,
+    /// The number of times seats became available after there were none
+    pub openings: usize,
+    /// How long seats stayed available on average, in seconds, over openings that have closed
+    pub average_open_secs: Option<i64>,
+    /// The number of openings that started in each hour of the day
+    pub openings_by_hour: [usize; 24],
+    pub current_seats: i16,
+    pub trend: Trend,
+}
+
+impl AllocationReport {
+    /// Summarises the observations of one allocation, which must be in the order they were recorded.
+    pub fn new(observations: &[&Observation]) -> Option<Self> {
+        let first = observations.first()?;
+        let last = observations.last()?;
+
+        let mut openings = 0;
+        let mut openings_by_hour = [0; 24];
+        let mut open_durations = Vec::new();
+        let mut opened_at: Option<DateTime<Local>> = None;
+
+        for pair in observations.windows(2) {
+            let (previous, current) = (pair[0], pair[1]);
+            if previous.seats <= 0 && current.seats > 0 {
+                openings += 1;
+                openings_by_hour[current.timestamp.hour() as usize] += 1;
+                opened_at = Some(current.timestamp);
+            } else if previous.seats > 0 && current.seats <= 0 {
+                // Seats that were already available when recording started
+                // did not open at a known time, so they are not counted
+                if let Some(opened_at) = opened_at.take() {
+                    open_durations.push(current.timestamp - opened_at);
+                }
+            }
+        }
+
+        let average_open_secs = (!open_durations.is_empty()).then(|| {
+            let total: Duration = open_durations.iter().sum();
+            total.num_seconds() / open_durations.len() as i64
+        });
+
+        let window = &observations[observations.len().saturating_sub(REPORT_TREND_WINDOW)..];
+        let trend = match last.seats.cmp(&window[0].seats) {
+            std::cmp::Ordering::Greater => Trend::Rising,
+            std::cmp::Ordering::Less => Trend::Falling,
+            std::cmp::Ordering::Equal => Trend::Steady,
+        };
+
+        Some(Self {
+            unit_code: first.unit_code.clone(),
+            semester: first.semester.clone(),
+            activity_type: first.activity_type,
+            activity: first.activity,
+            observations: observations.len(),
+            first_seen: first.timestamp,
+            last_seen: last.timestamp,
+            openings,
+            average_open_secs,
+            openings_by_hour,
+            current_seats: last.seats,
+            trend,
+        })
+    }
+
+    /// The hour of the day in which seats opened most often.
+    pub fn peak_hour(&self) -> Option<u32> {
+        let (hour, openings) = self.openings_by_hour
+            .iter()
+            .enumerate()
+            .max_by_key(|&(hour, openings)| (*openings, std::cmp::Reverse(hour)))?;
+        (*openings > 0).then_some(hour as u32)
+    }
+}
+
+/// Summarises every allocation in `observations`, in the order they were first seen.
+pub fn summarise(observations: &[Observation]) -> Vec<AllocationReport> {
+    let mut groups: Vec<(AllocationKey, Vec<&Observation>)> = Vec::new();
+    for observation in observations.iter() {
+        let key = observation.key();
+        match groups.iter_mut().find(|(group, _)| *group == key) {
+            Some((_, group)) => group.push(observation),
+            None => groups.push((key, vec![observation])),
+        }
+    }
+
+    groups
+        .iter()
+        .filter_map(|(_, group)| AllocationReport::new(group))
+        .collect()
+}
+
+struct OpenDuration(Option<i64>);
+
+impl fmt::Display for OpenDuration {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let Some(seconds) = self.0 else {
+            return write!(f, "-");
+        };
+
+        match seconds {
+            0..=59 => write!(f, "{}s", seconds),
+            60..=3599 => write!(f, "{}m", seconds / 60),
+            _ => write!(f, "{}h {}m", seconds / 3600, seconds % 3600 / 60),
+        }
+    }
+}
+
+struct PeakHour(Option<u32>);
+
+impl fmt::Display for PeakHour {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self.0 {
+            Some(hour) => write!(f, "{:0>2}:00-{:0>2}:00", hour, (hour + 1) % 24),
+            None => write!(f, "-"),
+        }
+    }
+}
+
+const COLUMNS: [&str; 10] = [
+    "unit", 
+    "session", 
+    "activity", 
+    "observations", 
+    "openings", 
+    "avg open", 
+    "peak hour", 
+    "seats", 
+    "trend", 
+    "last seen"
+];
+
+fn row(report: &AllocationReport) -> [String; 10] {
+    [
+        report.unit_code.clone(),
+        report.semester.to_string(),
+        format!("{} {}", report.activity_type.checkbox_id_suffix(), report.activity),
+        report.observations.to_string(),
+        report.openings.to_string(),
+        OpenDuration(report.average_open_secs).to_string(),
+        PeakHour(report.peak_hour()).to_string(),
+        report.current_seats.to_string(),
+        report.trend.to_string(),
+        report.last_seen.format("%d/%m/%y %H:%M").to_string(),
+    ]
+}
+
+pub fn print_table(reports: &[AllocationReport]) {
+    if reports.is_empty() {
+        return;
+    }
+
+    let rows: Vec<[String; 10]> = reports.iter().map(row).collect();
+    print_columns(&COLUMNS, &rows);
+}
+
+/// Prints rows under a bold header with every column padded to its widest cell.
+pub(crate) fn print_columns<const N: usize>(columns: &[&str; N], rows: &[[String; N]]) {
+    let mut widths = columns.map(str::len);
+    for row in rows.iter() {
+        for (width, cell) in widths.iter_mut().zip(row.iter()) {
+            *width = (*width).max(cell.chars().count());
+        }
+    }
+
+    let header: Vec<String> = columns
+        .iter()
+        .zip(widths.iter())
+        .map(|(column, width)| format!("{:<width$}", column, width = width))
+        .collect();
+    println!("{}", header.join("  ").trim_end().bold());
+
+    for row in rows.iter() {
+        let line: Vec<String> = row
+            .iter()
+            .zip(widths.iter())
+            .map(|(cell, width)| format!("{:<width$}", cell, width = width))
+            .collect();
+        println!("{}", line.join("  ").trim_end());
+    }
+}
+
+pub(crate) fn csv_field(value: &str) -> String {
+    if value.contains([',', '"', '\n']) {
+        format!("\"{}\"", value.replace('"', "\"\""))
+    } else {
+        value.to_owned()
+    }
+}
+
+pub fn print_csv(reports: &[AllocationReport]) {
+    print!("{}", csv(reports));
+}
+
+/// The reports as CSV with a header row, one line per report.
+pub fn csv(reports: &[AllocationReport]) -> String {
+    let mut csv = String::from(
+        "unit_code,semester,activity_type,activity,observations,openings,average_open_secs,\
+        peak_hour,current_seats,trend,first_seen,last_seen\n"
+    );
+
+    for report in reports.iter() {
+        let fields = [
+            report.unit_code.clone(),
+            report.semester.to_string(),
+            report.activity_type.checkbox_id_suffix().to_owned(),
+            report.activity.to_string(),
+            report.observations.to_string(),
+            report.openings.to_string(),
+            report.average_open_secs.map(|secs| secs.to_string()).unwrap_or_default(),
+            report.peak_hour().map(|hour| hour.to_string()).unwrap_or_default(),
+            report.current_seats.to_string(),
+            report.trend.to_string(),
+            report.first_seen.to_rfc3339(),
+            report.last_seen.to_rfc3339(),
+        ];
+        let fields: Vec<String> = fields.iter().map(|field| csv_field(field)).collect();
+        csv.push_str(&fields.join(","));
+        csv.push('\n');
+    }
+    csv
+}
+
+pub fn print_json(reports: &[AllocationReport]) -> serde_json::Result<()> {
+    println!("{}", serde_json::to_string_pretty(reports)?);
+    Ok(())
+}
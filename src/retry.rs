@@ -0,0 +1,133 @@
+use std::fmt;
+use std::future::Future;
+
+use log::warn;
+use rand::Rng;
+use serde::Deserialize;
+use tokio::time::{self, Duration};
+
+use crate::consts::{
+    DEFAULT_INITIAL_DELAY_MS,
+    DEFAULT_JITTER,
+    DEFAULT_MAX_ATTEMPTS,
+    DEFAULT_MAX_CONSECUTIVE_FAILURES,
+    DEFAULT_MAX_DELAY_MS,
+    DEFAULT_MULTIPLIER
+};
+use crate::error::{Error, Result};
+use crate::outcome::FindStep;
+
+/// How often and how quickly a failing step is attempted again. Only errors
+/// that are [retryable](Error::is_retryable) are retried.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RetryPolicy {
+    pub max_attempts: u32,
+    /// The delay before the first retry, in milliseconds
+    pub initial_delay: u64,
+    /// The largest delay between two attempts, in milliseconds
+    pub max_delay: u64,
+    pub multiplier: f64,
+    /// The fraction by which each delay is randomly lengthened or shortened
+    pub jitter: f64,
+}
+
+impl Default for RetryPolicy {
+    fn default() -> Self {
+        Self {
+            max_attempts: DEFAULT_MAX_ATTEMPTS,
+            initial_delay: DEFAULT_INITIAL_DELAY_MS,
+            max_delay: DEFAULT_MAX_DELAY_MS,
+            multiplier: DEFAULT_MULTIPLIER,
+            jitter: DEFAULT_JITTER,
+        }
+    }
+}
+
+impl RetryPolicy {
+    /// The delay before the given retry, where the first retry is `1`.
+    pub fn delay(&self, retry: u32) -> Duration {
+        let exponent = retry.saturating_sub(1) as i32;
+        let delay = (self.initial_delay as f64 * self.multiplier.powi(exponent)).min(self.max_delay as f64);
+
+        let jitter = self.jitter.clamp(0.0, 1.0);
+        let factor = if jitter > 0.0 {
+            1.0 + rand::thread_rng().gen_range(-jitter..=jitter)
+        } else {
+            1.0
+        };
+        Duration::from_millis((delay * factor) as u64)
+    }
+
+    /// Runs `operation` until it succeeds, fails with an error that is not retryable
+    /// or runs out of attempts. `step` describes the operation in log messages.
+    pub async fn retry<S, T, E, F, Fut>(&self, step: S, mut operation: F) -> Result<T>
+    where
+        S: fmt::Display,
+        E: Into<Error>,
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        let mut attempt = 1;
+
+        loop {
+            let error = match operation().await {
+                Ok(value) => return Ok(value),
+                Err(e) => e.into(),
+            };
+
+            if attempt >= self.max_attempts || !error.is_retryable() {
+                return Err(error);
+            }
+
+            let delay = self.delay(attempt);
+            warn!(
+                "Retrying {} (attempt {}/{}) in {:.2?}: {}",
+                step,
+                attempt + 1,
+                self.max_attempts,
+                delay,
+                error
+            );
+            time::sleep(delay).await;
+            attempt += 1;
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct RetryConfig {
+    /// Loading the public timetable and locating the search bar
+    pub navigation: RetryPolicy,
+    /// Filtering, searching for and selecting a unit
+    pub search: RetryPolicy,
+    /// Reading allocations from the timetable
+    pub scraping: RetryPolicy,
+    /// The number of consecutive failed searches after which the browser session is recreated
+    pub max_consecutive_failures: u32,
+}
+
+impl Default for RetryConfig {
+    fn default() -> Self {
+        Self {
+            navigation: RetryPolicy::default(),
+            search: RetryPolicy::default(),
+            scraping: RetryPolicy::default(),
+            max_consecutive_failures: DEFAULT_MAX_CONSECUTIVE_FAILURES,
+        }
+    }
+}
+
+impl RetryConfig {
+    pub fn policy(&self, step: FindStep) -> &RetryPolicy {
+        match step {
+            FindStep::LocateInteractees => &self.navigation,
+            FindStep::ToggleAdvancedFilter
+            | FindStep::SearchTimetable
+            | FindStep::SelectUnit
+            | FindStep::ResetTimetable => &self.search,
+            FindStep::SearchQuery => &self.scraping,
+        }
+    }
+}
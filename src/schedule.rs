@@ -0,0 +1,277 @@
+use chrono::{DateTime, Datelike, Local, NaiveDate, NaiveTime, TimeZone};
+use serde::{Deserialize, Deserializer};
+use serde::de::{self, Unexpected};
+use tokio::time::{Duration, Instant};
+
+use crate::allocation::{Day, TwentyFourHourTime};
+use crate::consts::{DEFAULT_BACKOFF_MULTIPLIER, DEFAULT_INTERVAL, DEFAULT_MAX_INTERVAL};
+use crate::query::FinderQuery;
+
+/// When and how often `watch` searches for each query.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScheduleConfig {
+    /// Seconds between searches for queries without their own interval
+    #[serde(deserialize_with = "deserialize_interval")]
+    pub interval: u64,
+    /// Times during which nothing is searched for
+    pub quiet_hours: Vec<QuietHours>,
+    /// Times during which every query is searched for at a different interval
+    pub windows: Vec<PollWindow>,
+    pub backoff: BackoffConfig,
+}
+
+impl Default for ScheduleConfig {
+    fn default() -> Self {
+        Self {
+            interval: DEFAULT_INTERVAL,
+            quiet_hours: Vec::new(),
+            windows: Vec::new(),
+            backoff: BackoffConfig::default(),
+        }
+    }
+}
+
+/// A time of day range that is limited to some weekdays or dates, e.g. enrolment days.
+/// The range wraps around midnight if `end` is before `start`.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct QuietHours {
+    pub start: TwentyFourHourTime,
+    pub end: TwentyFourHourTime,
+    #[serde(default)]
+    pub days: Vec<Day>,
+    #[serde(default)]
+    pub dates: Vec<NaiveDate>,
+}
+
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PollWindow {
+    pub start: TwentyFourHourTime,
+    pub end: TwentyFourHourTime,
+    #[serde(default)]
+    pub days: Vec<Day>,
+    #[serde(default)]
+    pub dates: Vec<NaiveDate>,
+    #[serde(deserialize_with = "deserialize_interval")]
+    pub interval: u64,
+}
+
+/// How much longer to wait between searches while the timetable is unresponsive.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct BackoffConfig {
+    /// The interval is multiplied by this after every consecutive failed search
+    pub multiplier: f64,
+    /// The longest interval to back off to, in seconds
+    pub max_interval: u64,
+}
+
+impl Default for BackoffConfig {
+    fn default() -> Self {
+        Self { multiplier: DEFAULT_BACKOFF_MULTIPLIER, max_interval: DEFAULT_MAX_INTERVAL }
+    }
+}
+
+fn deserialize_interval<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u64, D::Error> {
+    let interval = u64::deserialize(deserializer)?;
+    if interval == 0 {
+        return Err(de::Error::invalid_value(Unexpected::Unsigned(0), &"a positive number of seconds"));
+    }
+    Ok(interval)
+}
+
+pub(crate) fn deserialize_optional_interval<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Option<u64>, D::Error> {
+    deserialize_interval(deserializer).map(Some)
+}
+
+fn naive_time(time: TwentyFourHourTime) -> NaiveTime {
+    NaiveTime::from_hms_opt(time.hours as u32, time.minutes as u32, 0).unwrap_or(NaiveTime::MIN)
+}
+
+/// Whether `now` falls within `start..end` on one of `days` and `dates` (or any day if they are empty).
+fn in_window(
+    start: TwentyFourHourTime, 
+    end: TwentyFourHourTime, 
+    days: &[Day], 
+    dates: &[NaiveDate], 
+    now: DateTime<Local>
+) -> bool {
+    let (start, end, time) = (naive_time(start), naive_time(end), now.time());
+    let wraps = end <= start;
+
+    // After midnight, a window that wraps around midnight belongs to the previous day
+    let date = if wraps && time < end { now.date_naive().pred_opt().unwrap_or(now.date_naive()) } else { now.date_naive() };
+    let weekday = Day::try_from(date.weekday().number_from_monday() as u64).ok();
+
+    let day_matches = days.is_empty() || weekday.is_some_and(|weekday| days.contains(&weekday));
+    let date_matches = dates.is_empty() || dates.contains(&date);
+    let time_matches = if wraps { time >= start || time < end } else { time >= start && time < end };
+
+    day_matches && date_matches && time_matches
+}
+
+impl QuietHours {
+    pub fn contains(&self, now: DateTime<Local>) -> bool {
+        in_window(self.start, self.end, &self.days, &self.dates, now)
+    }
+
+    /// When the quiet hours that contain `now` end.
+    fn end_after(&self, now: DateTime<Local>) -> DateTime<Local> {
+        next_start(self.end, now)
+    }
+}
+
+impl PollWindow {
+    pub fn contains(&self, now: DateTime<Local>) -> bool {
+        in_window(self.start, self.end, &self.days, &self.dates, now)
+    }
+}
+
+#[derive(Debug)]
+struct QueryTimer {
+    query: FinderQuery,
+    last_search: Option<Instant>,
+}
+
+/// Decides which queries are due to be searched for, keeping one timer per query.
+/// The timers are in the same order as the queries they were created from.
+///
+/// Intervals are worked out whenever a query is checked rather than when it was last
+/// searched for, so a window that starts applies to every query straight away.
+#[derive(Debug)]
+pub struct Scheduler {
+    config: ScheduleConfig,
+    timers: Vec<QueryTimer>,
+    failures: u32,
+    deferred_until: Option<Instant>,
+}
+
+impl Scheduler {
+    /// Creates a scheduler for which every query is due straight away.
+    pub fn new(config: ScheduleConfig, queries: &[FinderQuery]) -> Self {
+        let timers = queries
+            .iter()
+            .map(|query| QueryTimer { query: query.clone(), last_search: None })
+            .collect();
+        Self { config, timers, failures: 0, deferred_until: None }
+    }
+
+    /// Applies a new config and queries. Queries that were already scheduled keep
+    /// their timer while new queries are due straight away. Any deferral is dropped.
+    pub fn reload(&mut self, config: ScheduleConfig, queries: &[FinderQuery]) {
+        let timers = queries
+            .iter()
+            .map(|query| {
+                let last_search = self.timers
+                    .iter()
+                    .find(|timer| timer.query == *query)
+                    .and_then(|timer| timer.last_search);
+                QueryTimer { query: query.clone(), last_search }
+            })
+            .collect();
+
+        self.config = config;
+        self.timers = timers;
+        // Quiet hours are worked out again from the new config
+        self.deferred_until = None;
+    }
+
+    /// The interval between searches for `query` at `now`, including any back-off.
+    pub fn interval(&self, query: &FinderQuery, now: DateTime<Local>) -> Duration {
+        let window = self.config.windows.iter().find(|window| window.contains(now));
+        let interval = match window {
+            Some(window) => window.interval,
+            None => query.interval.unwrap_or(self.config.interval),
+        };
+        if self.failures == 0 {
+            return Duration::from_secs(interval);
+        }
+
+        let backoff = &self.config.backoff;
+        let multiplier = backoff.multiplier.max(1.0).powi(self.failures.min(i32::MAX as u32) as i32);
+        let max_interval = backoff.max_interval.max(interval) as f64;
+        Duration::from_secs_f64((interval as f64 * multiplier).min(max_interval))
+    }
+
+    /// When the quiet hours that `now` falls within end.
+    pub fn quiet_until(&self, now: DateTime<Local>) -> Option<DateTime<Local>> {
+        self.config.quiet_hours
+            .iter()
+            .filter(|quiet_hours| quiet_hours.contains(now))
+            .map(|quiet_hours| quiet_hours.end_after(now))
+            .max()
+    }
+
+    fn next_due(&self, timer: &QueryTimer, now: DateTime<Local>) -> Option<Instant> {
+        timer.last_search.map(|last_search| last_search + self.interval(&timer.query, now))
+    }
+
+    /// The indices of the queries that are due to be searched for.
+    pub fn due(&self, now: Instant, local: DateTime<Local>) -> Vec<usize> {
+        if self.deferred_until.is_some_and(|until| now < until) {
+            return Vec::new();
+        }
+
+        self.timers
+            .iter()
+            .enumerate()
+            .filter(|(_, timer)| self.next_due(timer, local).is_none_or(|next_due| next_due <= now))
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    /// When to check for due queries next: when the next query is due or the
+    /// next window starts, whichever is sooner.
+    pub fn next_wake(&self, now: Instant, local: DateTime<Local>) -> Instant {
+        if let Some(until) = self.deferred_until.filter(|&until| now < until) {
+            return until;
+        }
+
+        let next_due = self.timers
+            .iter()
+            .map(|timer| self.next_due(timer, local).unwrap_or(now))
+            .min()
+            .unwrap_or_else(|| now + Duration::from_secs(self.config.interval));
+
+        let next_window = self.config.windows
+            .iter()
+            .filter_map(|window| (next_start(window.start, local) - local).to_std().ok())
+            .min()
+            .map(|until| now + until);
+
+        next_window.map_or(next_due, |next_window| next_due.min(next_window))
+    }
+
+    /// Postpones every query until `until`, e.g. the end of quiet hours.
+    pub fn defer(&mut self, until: Instant) {
+        self.deferred_until = Some(until);
+    }
+
+    /// Records that the queries at `indices` have just been searched for. A failed search
+    /// backs off every query, since it usually means the timetable is unresponsive.
+    pub fn completed(&mut self, indices: &[usize], succeeded: bool) {
+        self.failures = if succeeded { 0 } else { self.failures.saturating_add(1) };
+
+        let now = Instant::now();
+        for &index in indices.iter() {
+            self.timers[index].last_search = Some(now);
+        }
+    }
+
+    /// The number of consecutive failed searches.
+    pub fn failures(&self) -> u32 {
+        self.failures
+    }
+}
+
+/// The next time of day `time` occurs after `now`.
+fn next_start(time: TwentyFourHourTime, now: DateTime<Local>) -> DateTime<Local> {
+    let time = naive_time(time);
+    let date = if now.time() < time { now.date_naive() } else { now.date_naive() + chrono::Duration::days(1) };
+    Local
+        .from_local_datetime(&date.and_time(time))
+        .earliest()
+        .unwrap_or(now + chrono::Duration::hours(1))
+}
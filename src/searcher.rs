@@ -1,45 +1,82 @@
-use std::error::Error;
+use std::cmp::Reverse;
 use std::collections::HashMap;
 
 use thirtyfour::prelude::*;
 
 use crate::consts::ROWS_IN_TABLE;
-use crate::error::{TableRowError, TableError};
+use crate::error::{Result, TableRowError, TableError};
 use crate::methods::format_u64;
 use crate::query::FinderQuery;
-use crate::allocation::{Allocation, AllocationResult};
+use crate::allocation::{Allocation, AllocationResult, Day, DAYS};
 use crate::selector::*;
 
 pub struct TimetableSearcher<'a> {
     driver: &'a WebDriver,
-    query: &'a FinderQuery
 }
 
 impl<'a> TimetableSearcher<'a> {
-    pub fn new(driver: &'a WebDriver, query: &'a FinderQuery) -> Self {
-        Self { driver, query }
+    pub fn new(driver: &'a WebDriver) -> Self {
+        Self { driver }
     }
 
-    pub async fn search(&self) -> AllocationResult {
-        let mut timetable_row = 1;
-        let timetable_column = format_u64(ALLOCATION_FORMAT.as_str(), self.query.day as u64);
+    /// Every allocation on the query's days that matches the query, ranked by
+    /// the query's preference and then by the number of seats left.
+    pub async fn search(&self, query: &FinderQuery) -> AllocationResult {
+        let mut allocations = Vec::new();
+        for &day in query.days.iter() {
+            let on_day = self.allocations_on(day).await?;
+            allocations.extend(on_day.into_iter().filter(|allocation| query.matches(allocation)));
+        }
 
-        while let Ok(ref event) = self.timetabled_event(&timetable_column, timetable_row).await {
-            event.click().await?;
-            
-            let allocation = self.allocation_from_table(&timetable_column, timetable_row).await?;
-            if allocation.activity == self.query.activity {
-                return Ok(if allocation.seats > 0 { Some(allocation) } else { None })
+        let preference = &query.activity;
+        allocations.sort_by_key(|allocation| {
+            (preference.preference(allocation.activity), Reverse(allocation.seats))
+        });
+        Ok(allocations)
+    }
+
+    /// Every allocation in the timetable, from Monday to Sunday.
+    pub async fn list(&self) -> AllocationResult {
+        let mut allocations = Vec::new();
+        for day in DAYS {
+            allocations.extend(self.allocations_on(day).await?);
+        }
+        Ok(allocations)
+    }
+
+    /// Leaves the details of an activity if they are shown, e.g. after reading them failed.
+    pub async fn return_to_timetable(&self) -> WebDriverResult<()> {
+        let go_back_buttons = self.driver.find_all(By::XPath(GO_BACK_BUTTON)).await?;
+        for go_back_button in go_back_buttons.iter() {
+            if go_back_button.is_displayed().await? {
+                go_back_button.click().await?;
             }
-            
-            self.go_back_to_timetable().await?;
-            timetable_row += 1;
         }
+        Ok(())
+    }
+
+    /// Every allocation in the column of `day`. The events are counted up front so that
+    /// an event that cannot be read is an error rather than the end of the column.
+    async fn allocations_on(&self, day: Day) -> AllocationResult {
+        let column = self.driver
+            .query(By::XPath(format_u64(DAY_COLUMN_FORMAT.as_str(), day as u64)))
+            .first()
+            .await?;
+        let events = column.find_all(By::XPath("./div")).await?.len() as u64;
 
-        Ok(None)
+        let timetable_column = format_u64(ALLOCATION_FORMAT.as_str(), day as u64);
+        let mut allocations = Vec::with_capacity(events as usize);
+
+        for timetable_row in 1..=events {
+            let event = self.timetabled_event(&timetable_column, timetable_row).await?;
+            event.click().await?;
+            allocations.push(self.allocation_from_table(&timetable_column, timetable_row).await?);
+            self.go_back_to_timetable().await?;
+        }
+        Ok(allocations)
     }
 
-    async fn allocation_from_table(&self, timetable_column: &str, timetable_row: u64) -> Result<Allocation, Box<dyn Error>> {
+    async fn allocation_from_table(&self, timetable_column: &str, timetable_row: u64) -> Result<Allocation> {
         let mut allocation_table = HashMap::with_capacity(ROWS_IN_TABLE);
         let mut table_rows = self.table_rows().await?; 
         let mut table_row_number = 0;
@@ -92,9 +129,7 @@ impl<'a> TimetableSearcher<'a> {
 
         if allocation_table.len() != ROWS_IN_TABLE {
             return Err(
-                Box::new(
-                    TableError::TableSizeError(ROWS_IN_TABLE, allocation_table.len())
-                )
+                TableError::TableSizeError(ROWS_IN_TABLE, allocation_table.len()).into()
             );
         }
 
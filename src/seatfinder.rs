@@ -1,32 +1,44 @@
-use std::fs::File;
-use std::error::Error;
-use std::io::BufReader;
+use std::future::Future;
+use std::path::Path;
 use std::time::Instant;
 use std::process::Child;
 
-use env_logger;
-use log::info;
+use log::{debug, error, info, warn};
 use colored::{self, Colorize};
 use chrono;
-use serde_json::{self, Value};
 use thirtyfour::prelude::*;
-use tokio::{time::{self, Duration}, runtime::Runtime};
+use tokio::{time, runtime::Runtime};
 
-use crate::consts::{CONFIG_FILE, TIMED};
-use crate::error::OfferingError;
-use crate::query::{FinderQuery, FinderConfig};
+use crate::check::ConfigReport;
+use crate::config::Config;
+use crate::consts::TIMED;
+use crate::error::{Error, OfferingError, Result};
+use crate::query::{ConfigOverrides, FinderQuery, FinderConfig};
 use crate::methods::{
     format_str, 
     format_usize,
     chromedriver_process, 
-    parse_queries,
-    annoy,
+    wait_for_port,
     single_offering,
     multiple_offerings, 
+    pinned_offering,
 };
 use crate::selector::*;
-use crate::allocation::AllocationResult;
+use crate::allocation::Allocation;
+use crate::offering::Offering;
+use crate::session::Session;
+use crate::outcome::{print_summary, FindStep, QueryOutcome};
 use crate::searcher::TimetableSearcher;
+use crate::reload::{ConfigWatcher, QueryDiff};
+use crate::notifier::{Notification, Notifiers};
+use crate::tracker::{SeatTracker, Transition};
+use crate::group::{GroupResolver, Resolution};
+use crate::planner::Planner;
+use crate::calendar::{Calendar, CalendarEntry};
+use crate::history::{AllocationKey, HistoryConfig, HistoryStore, Observation};
+use crate::report::{self, ReportFormat, ReportOptions};
+use crate::listing::{self, ListOptions};
+use crate::schedule::Scheduler;
 
 #[derive(Debug)]
 pub struct Interactees {
@@ -41,47 +53,41 @@ pub struct SeatFinder {
     config: FinderConfig,
     chromedriver: Option<Child>,
     queries: Vec<FinderQuery>,
+    notifiers: Notifiers,
+    tracker: SeatTracker,
+    groups: GroupResolver,
+    planner: Planner,
+    history: Option<HistoryStore>,
+    /// The allocations in the calendar that was last exported
+    exported: Option<Vec<AllocationKey>>,
 }
 
 impl SeatFinder {
-    pub async fn new() -> Self {
-        match SeatFinder::try_new().await {
-            Ok(seatfinder) => seatfinder,
-            Err(e) => panic!("Error constructing seatfinder: {}", e),
-        }
+    pub async fn try_new(config_path: &Path, overrides: &ConfigOverrides) -> Result<Self> {
+        let config = Config::load(config_path, overrides)?;
+        SeatFinder::try_from_config(config).await
     }
 
-    pub async fn try_new() -> Result<Self, Box<dyn Error>> {
-        let file = File::open(CONFIG_FILE)?;
-        let reader = BufReader::new(file);
-        
-        let json_config: Value = serde_json::from_reader(reader)?;
-        let config = FinderConfig::try_new(json_config)?;
+    pub async fn try_from_config(Config { finder: config, queries }: Config) -> Result<Self> {
+        // Opened before chromedriver is started so that failing to open it leaves nothing running
+        let history = if config.history.enabled {
+            Some(HistoryStore::open(config.history.clone())?)
+        } else {
+            None
+        };
 
         let chromedriver = if config.run_chromedriver {
-            Some(chromedriver_process(config.port)?)
+            let child = chromedriver_process(config.port)?;
+            if !wait_for_port(config.port).await {
+                warn!("chromedriver is not listening on port {} yet", config.port);
+            }
+            Some(child)
         } else {
             None
         };
 
-        let mut capabilities = DesiredCapabilities::chrome();
-        if config.headless {
-            capabilities.add_arg("--headless")?;
-        }
-        
-        let server_url = format!("http://localhost:{}", config.port);
-        let driver = match WebDriver::new(server_url, capabilities).await {
+        let driver = match new_driver(&config).await {
             Ok(driver) => driver,
-            Err(e) => {
-                if let Some(mut child) = chromedriver {
-                    child.kill()?;
-                }
-                return Err(Box::new(e));
-            }
-        };
-        
-        let queries = match parse_queries() {
-            Ok(queries) => queries,
             Err(e) => {
                 if let Some(mut child) = chromedriver {
                     child.kill()?;
@@ -90,7 +96,92 @@ impl SeatFinder {
             }
         };
 
-        Ok(Self { driver, config, chromedriver, queries })
+        let notifiers = Notifiers::new(&config.notifiers, &config.notify);
+        let tracker = SeatTracker::new(config.alerts.clone());
+        let groups = GroupResolver::new(&queries);
+        let planner = Planner::new(config.planner.clone());
+        Ok(Self { 
+            driver, 
+            config, 
+            chromedriver, 
+            queries, 
+            notifiers, 
+            tracker, 
+            groups, 
+            planner, 
+            history, 
+            exported: None 
+        })
+    }
+
+    /// Swaps in the queries and notification settings of a freshly parsed config
+    /// while keeping the current WebDriver session.
+    pub fn reload(&mut self, Config { finder, queries }: Config) {
+        QueryDiff::new(&self.queries, &queries).log();
+
+        let port_changed = !self.config.run_chromedriver && finder.port != self.config.port;
+        if port_changed || finder.headless != self.config.headless || finder.run_chromedriver != self.config.run_chromedriver {
+            warn!("Changes to `port`, `headless` and `run_chromedriver` only take effect after a restart.");
+        }
+
+        if finder.notifiers != self.config.notifiers || finder.notify != self.config.notify {
+            self.notifiers = Notifiers::new(&finder.notifiers, &finder.notify);
+        }
+
+        self.config.public_timetable_url = finder.public_timetable_url;
+        self.config.retry = finder.retry;
+        self.config.notifiers = finder.notifiers;
+        self.config.notify = finder.notify;
+        self.tracker.reload(finder.alerts.clone(), &queries);
+        self.groups.reload(&queries);
+        self.planner.reload(finder.planner.clone());
+        if finder.history != self.config.history {
+            self.reopen_history(&finder.history);
+        }
+        self.config.alerts = finder.alerts;
+        self.config.history = finder.history;
+        self.config.planner = finder.planner;
+        self.config.calendar = finder.calendar;
+        self.config.schedule = finder.schedule;
+        self.exported = None;
+        self.queries = queries;
+    }
+
+    /// Replaces the browser session with a new one, e.g. after the current
+    /// session has stopped responding.
+    pub async fn restart_session(&mut self) -> Result<()> {
+        let driver = new_driver(&self.config).await?;
+        let previous = std::mem::replace(&mut self.driver, driver);
+        if let Err(e) = previous.quit().await {
+            debug!("Could not quit the previous session: {}", e);
+        }
+        Ok(())
+    }
+
+    /// Recovers from a crashed browser or chromedriver by restarting chromedriver
+    /// (if it was started by seatfinder and is no longer running) and the session.
+    pub async fn recover_session(&mut self) -> Result<()> {
+        if self.config.run_chromedriver && !self.chromedriver_is_running() {
+            warn!("chromedriver is no longer running, restarting it...");
+            if let Some(mut child) = self.chromedriver.take() {
+                let _ = child.kill();
+                let _ = child.wait();
+            }
+
+            self.chromedriver = Some(chromedriver_process(self.config.port)?);
+            if !wait_for_port(self.config.port).await {
+                warn!("chromedriver is not listening on port {} yet", self.config.port);
+            }
+        }
+
+        self.restart_session().await
+    }
+
+    fn chromedriver_is_running(&mut self) -> bool {
+        match self.chromedriver {
+            Some(ref mut child) => matches!(child.try_wait(), Ok(None)),
+            None => false,
+        }
     }
 
     pub fn add_query(&mut self, query: FinderQuery) -> &mut Self {
@@ -98,81 +189,323 @@ impl SeatFinder {
         self
     }
 
-    pub async fn seatfind(&self) {   
+    pub async fn seatfind(&mut self) -> Vec<(&FinderQuery, QueryOutcome)> {   
         if self.queries.is_empty() {
             info!("No queries to find.");
-            return;
+            return Vec::new();
         } 
 
-        for query in self.queries.iter() {
-            let interactees = match self.locate_interactees().await {
-                Ok(interactees) => interactees,
-                Err(e) => panic!("Error searching for units: {}", e),
-            };
-
-            if let Err(e) = self.toggle_advanced_filter(query).await {
-                panic!("Error toggling advanced filter: {}", e);
+        let mut outcomes = Vec::with_capacity(self.queries.len());
+        for index in 0..self.queries.len() {
+            let query = &self.queries[index];
+            let (outcome, unreset) = self.find(query).await;
+            match outcome {
+                QueryOutcome::Found(_) => {}
+                QueryOutcome::Error { step, ref cause } => error!("Error {} for {}: {}", step, query, cause),
+                _ => info!("No allocations found for {} matching the given query.", query.unit_code()),
             }
 
-            if let Err(e) = self.search_timetable(&interactees, query).await {
-                panic!("Error searching the timetable: {}", e);
-            }
-        
-            if let Err(e) = self.select_unit(query).await {
-                panic!("Error selecting the unit offering: {}", e);
-            }
-        
-            match self.search_query(&interactees.show_timetable_button, query).await {
-                Ok(opt) => match opt {
-                    Some(allocation) => allocation.notify_query_resolved(query.unit_code()),
-                    None => info!("No allocations found for {} matching the given query.", query.unit_code()),
+            self.record(index, &outcome);
+            self.announce(index, &outcome);
+            self.recover_if_needed(&outcome, unreset).await;
+            outcomes.push(outcome);
+        }
+
+        self.queries.iter().zip(outcomes).collect()
+    }
+
+    /// Searches for every query, returning `None` if any query failed
+    /// after exhausting its retries.
+    pub async fn seats_are_available(&mut self) -> Option<bool> {
+        let indices: Vec<usize> = (0..self.queries.len()).collect();
+        self.search(&indices).await
+    }
+
+    /// Searches for the queries at `indices`, returning `None` if any of them
+    /// failed after exhausting its retries.
+    pub async fn search(&mut self, indices: &[usize]) -> Option<bool> {
+        let mut availability = false;
+        let mut failed = false;
+        for &index in indices.iter() {
+            let query = &self.queries[index];
+            let (outcome, unreset) = self.find(query).await;
+            match outcome {
+                QueryOutcome::Found(_) => availability = true,
+                QueryOutcome::Error { step, ref cause } => {
+                    error!("Error {} for {}: {}", step, query, cause);
+                    // Errors such as a unit without offerings will not go away
+                    // by themselves, so only the others count as failures
+                    failed |= cause.is_retryable();
                 }
-                Err(e) => panic!("Error searching for the query: {}", e)
+                _ => info!("No allocations found for {} matching the given query.", query.unit_code()),
             }
 
-            if let Err(e) = self.reset_timetable().await {
-                panic!("Error clearing the timetable: {}", e);
+            self.record(index, &outcome);
+            self.announce(index, &outcome);
+            self.recover_if_needed(&outcome, unreset).await;
+        }
+
+        (!failed).then_some(availability)
+    }
+
+    pub async fn offerings(&self, unit_code: &str) -> Result<Vec<String>> {
+        let interactees = self.locate_interactees().await?;
+        self.search_timetable(&interactees, unit_code).await?;
+
+        let (_, subcodes) = self.offering_subcodes().await?;
+        self.clear_timetable().await?;
+        Ok(subcodes)
+    }
+
+    /// Every allocation of the offering of `unit_code` in `session`, or of `subcode` if given.
+    pub async fn allocations(
+        &self, 
+        unit_code: &str, 
+        session: &Session, 
+        subcode: Option<&Offering>
+    ) -> Result<Vec<Allocation>> {
+        let interactees = self.locate_interactees().await?;
+        self.search_timetable(&interactees, unit_code).await?;
+        self.select_unit(unit_code, session, subcode).await?;
+        interactees.show_timetable_button.click().await?;
+
+        let allocations = TimetableSearcher::new(&self.driver).list().await?;
+        self.clear_timetable().await?;
+        Ok(allocations)
+    }
+
+    /// The chosen allocation of every query, leaving out alternatives of a group
+    /// other than the most preferred one with seats.
+    pub fn chosen(&self) -> Vec<CalendarEntry> {
+        self.planner
+            .chosen()
+            .into_iter()
+            .filter(|&(index, _)| match self.queries[index].alternative {
+                Some(ref alternative) => self.groups.best(&alternative.group) == Some(alternative.rank),
+                None => true,
+            })
+            .map(|(index, allocation)| CalendarEntry { 
+                unit_code: self.queries[index].unit_code(), 
+                allocation: allocation.clone() 
+            })
+            .collect()
+    }
+
+    /// Exports the chosen allocations to the calendar in the config (if any)
+    /// when they have changed since the last export.
+    pub fn export_calendar(&mut self) {
+        let Some(ref calendar) = self.config.calendar else {
+            return;
+        };
+
+        let entries = self.chosen();
+        let keys: Vec<AllocationKey> = entries
+            .iter()
+            .map(|entry| AllocationKey::new(&entry.unit_code, &entry.allocation))
+            .collect();
+        if self.exported.as_ref() == Some(&keys) {
+            return;
+        }
+
+        match Calendar::new(calendar).write(&entries) {
+            Ok(count) => {
+                info!("Exported {} allocations to {}", count, calendar.path.display());
+                self.exported = Some(keys);
             }
+            Err(e) => error!("Error exporting to {}: {}", calendar.path.display(), e),
         }
     }
 
-    pub async fn seats_are_available(&self) -> Option<bool> {
-        let mut availability = false;
-        for query in self.queries.iter() {
-            let interactees = match self.locate_interactees().await {
-                Ok(interactees) => interactees,
-                Err(_) => return None,
+    /// Waits for notifications that are still being sent, e.g. before exiting.
+    pub async fn flush_notifications(&self) {
+        self.notifiers.wait().await;
+    }
+
+    pub async fn quit(self) -> Result<()> {
+        // chromedriver is killed even if the browser could not be closed
+        let quit = self.driver.quit().await;
+        if let Some(mut child) = self.chromedriver {
+            child.kill()?;
+        }
+        Ok(quit?)
+    }
+}
+
+impl SeatFinder {
+    fn reopen_history(&mut self, config: &HistoryConfig) {
+        if !config.enabled {
+            self.history = None;
+            return;
+        }
+
+        match HistoryStore::open(config.clone()) {
+            Ok(history) => self.history = Some(history),
+            Err(e) => error!("Could not open {}, keeping the previous history: {}", config.path.display(), e),
+        }
+    }
+
+    /// Appends the seats of the allocations found for the query at `index` to the history.
+    fn record(&mut self, index: usize, outcome: &QueryOutcome) {
+        let Some(ref mut history) = self.history else {
+            return;
+        };
+
+        for allocation in outcome.allocations() {
+            let observation = Observation::new(&self.queries[index].unit_code, allocation);
+            if let Err(e) = history.record(&observation) {
+                error!("Error recording to {}: {}", history.config().path.display(), e);
+            }
+        }
+    }
+
+    /// Notifies about the allocations found for the query at `index` whose seats have
+    /// changed in a way that is worth notifying about since the last notification. An
+    /// alternative of a group is only notified about while no better alternative has seats,
+    /// and allocations that clash are not notified about if `suppress_clashes` is set.
+    fn announce(&mut self, index: usize, outcome: &QueryOutcome) {
+        let query = &self.queries[index];
+        // After an error nothing is known about the seats, so what was found before is kept
+        if !outcome.is_error() {
+            self.planner.update(index, query, outcome.allocations());
+
+            if let Some(ref alternative) = query.alternative {
+                let usable = |allocation: &Allocation| {
+                    query.has_seats(allocation)
+                        && !self.planner.suppresses(&self.planner.clashes(index, &self.queries, allocation))
+                };
+                self.groups.update(alternative, outcome.allocations().iter().any(usable));
+            }
+        }
+
+        for allocation in outcome.allocations() {
+            let Some(transition) = self.tracker.observe(query, allocation) else {
+                continue;
+            };
+
+            let clashes = match transition {
+                Transition::SoldOut => Vec::new(),
+                _ => self.planner.clashes(index, &self.queries, allocation),
+            };
+            if self.planner.suppresses(&clashes) {
+                // Notified about once it no longer clashes
+                debug!("Not notifying about activity {} of {}: it clashes", allocation.activity, query);
+                self.tracker.forget(query, allocation);
+                continue;
+            }
+
+            let resolution = match query.alternative {
+                Some(ref alternative) => self.groups.resolve(alternative, transition),
+                None => Resolution::Notify { better_than: None },
+            };
+            let better_than = match resolution {
+                Resolution::Notify { better_than } => better_than,
+                Resolution::Suppress => {
+                    // Notified about once it becomes the best alternative with seats
+                    debug!("Not notifying about activity {} of {}: a better alternative has seats", allocation.activity, query);
+                    self.tracker.forget(query, allocation);
+                    continue;
+                }
             };
 
-            self.toggle_advanced_filter(query).await.ok()?;
-            self.search_timetable(&interactees, query).await.ok()?;
-            self.select_unit(query).await.ok()?;
-            
-            if let Ok(opt) = self.search_query(&interactees.show_timetable_button, query).await {
-                match opt {
-                    Some(allocation) => {
-                        allocation.notify_query_resolved(query.unit_code());
-                        availability = true;
-                    },
-                    None => info!("No allocations found for {} matching the given query.", query.unit_code()),
+            match transition {
+                Transition::SoldOut => {
+                    let taken = format!("The seats in activity {} of {} have been taken", allocation.activity, query.unit_code);
+                    println!("{}", taken.yellow());
                 }
+                _ => allocation.notify_query_resolved(query.unit_code()),
             }
+            if let Some(ref alternative) = query.alternative {
+                let matched = match better_than {
+                    Some(better_than) => format!("This is {}, better than alternative {}", alternative, better_than),
+                    None => format!("This is {}", alternative),
+                };
+                println!("{}", matched.cyan());
+            }
+            for clash in clashes.iter() {
+                let clashing = format!("Activity {} of {} clashes with {}", allocation.activity, query.unit_code, clash);
+                println!("{}", clashing.yellow());
+            }
+
+            self.notifiers.dispatch(Notification {
+                query: query.clone(),
+                allocation: allocation.clone(),
+                transition,
+                better_than,
+                clashes,
+            });
+        }
+    }
 
-            self.reset_timetable().await.ok()?;
+    /// Recovers the session if `outcome` shows that it was lost, or if the timetable
+    /// could not be reset after the search, so that searching can continue with the next query.
+    async fn recover_if_needed(&mut self, outcome: &QueryOutcome, unreset: bool) {
+        let lost = matches!(outcome, QueryOutcome::Error { cause, .. } if cause.is_session_lost());
+        if !lost && !unreset {
+            return;
         }
 
-        Some(availability)
+        if lost {
+            warn!("The browser session was lost, recovering...");
+        } else {
+            warn!("The timetable was left in an unknown state, recovering the browser session...");
+        }
+        match self.recover_session().await {
+            Ok(()) => info!("Recovered the browser session."),
+            Err(e) => error!("Error recovering the browser session: {}", e),
+        }
     }
 
-    pub async fn quit(self) {
-        self.driver.quit().await.expect("webdriver did not succesfully quit");
-        if let Some(mut child) = self.chromedriver {
-            child.kill().expect("chromedriver did not succesfully quit");
+    /// Searches for a query, also returning whether the timetable could not be reset
+    /// afterwards and the session needs to be recovered before the next search.
+    async fn find(&self, query: &FinderQuery) -> (QueryOutcome, bool) {
+        let outcome = match self.try_find(query).await {
+            Ok(allocations) if allocations.is_empty() => QueryOutcome::NotFound,
+            Ok(allocations) if allocations.iter().any(|allocation| query.has_seats(allocation)) => QueryOutcome::Found(allocations),
+            Ok(allocations) => QueryOutcome::NoSeats(allocations),
+            Err((step, cause)) => QueryOutcome::Error { step, cause },
+        };
+
+        let reset = match outcome {
+            QueryOutcome::Error { .. } => self.reset_timetable().await.map_err(Error::from),
+            _ => self.attempt(FindStep::ResetTimetable, || self.reset_timetable()).await.map_err(|(_, e)| e),
+        };
+        let (outcome, unreset) = outcome.after_reset(reset);
+        if let Some(ref e) = unreset {
+            warn!("Could not clear the timetable after searching for {}: {}", query, e);
         }
+        (outcome, unreset.is_some())
+    }
+
+    async fn try_find(&self, query: &FinderQuery) -> Result<Vec<Allocation>, (FindStep, Error)> {
+        let interactees = self.attempt(FindStep::LocateInteractees, || self.locate_interactees()).await?;
+        self.attempt(FindStep::ToggleAdvancedFilter, || self.toggle_advanced_filter(query)).await?;
+        self.attempt(FindStep::SearchTimetable, || self.search_timetable(&interactees, &query.unit_code)).await?;
+        self.attempt(FindStep::SelectUnit, || self.select_unit(&query.unit_code, &query.session, query.subcode.as_ref())).await?;
+
+        // The timetable is only shown once since the button toggles it
+        interactees.show_timetable_button.click().await
+            .map_err(|e| (FindStep::SearchQuery, e.into()))?;
+        let searcher = TimetableSearcher::new(&self.driver);
+        self.attempt(FindStep::SearchQuery, || async {
+            // A failed attempt may have left the details of an activity open
+            searcher.return_to_timetable().await?;
+            searcher.search(query).await
+        }).await
+    }
+
+    async fn attempt<T, E, F, Fut>(&self, step: FindStep, operation: F) -> Result<T, (FindStep, Error)>
+    where
+        E: Into<Error>,
+        F: FnMut() -> Fut,
+        Fut: Future<Output = Result<T, E>>,
+    {
+        self.config.retry
+            .policy(step)
+            .retry(step, operation)
+            .await
+            .map_err(|e| (step, e))
     }
-}
 
-impl SeatFinder {
     async fn locate_interactees(&self) -> WebDriverResult<Interactees> {
         self.driver.goto(&self.config.public_timetable_url).await?;
 
@@ -188,10 +521,11 @@ impl SeatFinder {
     }
 
     async fn toggle_advanced_filter(&self, query: &FinderQuery) -> WebDriverResult<()> {
-        if let Some(ref start_time) = query.start_after {
-            // For some reason, the start time must be entered as one hour ahead of the actual start time
-            // so as to exclude prior allocations
-            let script = format!("document.getElementById('{START_TIME}').value = '{}';", start_time.progress_one_hour());
+        // For some reason, the start time must be entered as one hour ahead of the actual start time
+        // so as to exclude prior allocations. Start times from 23:00 are left out, since allocations
+        // are still filtered by the query after they are scraped
+        if let Some(start_time) = query.start_after.and_then(|time| time.checked_progress_one_hour()) {
+            let script = format!("document.getElementById('{START_TIME}').value = '{}';", start_time);
             self.driver.execute(script, Vec::new()).await?;
         }
 
@@ -213,15 +547,15 @@ impl SeatFinder {
     async fn search_timetable(
         &self, 
         Interactees { search_bar, search_button, show_timetable_button: _ }: &Interactees, 
-        query: &FinderQuery
+        unit_code: &str
     ) -> WebDriverResult<()> {
         search_bar.clear().await?;
-        search_bar.send_keys(&query.unit_code).await?;
+        search_bar.send_keys(unit_code).await?;
         search_button.wait_until().clickable().await?;
         search_button.click().await
     }
 
-    async fn select_unit(&self, query: &FinderQuery) -> Result<(), Box<dyn Error>> {
+    async fn offering_subcodes(&self) -> WebDriverResult<(Vec<WebElement>, Vec<String>)> {
         let selected_results = self.driver
             .query(By::XPath(UNIT_OFFERINGS))
             .all_from_selector()
@@ -233,47 +567,42 @@ impl SeatFinder {
             subcodes.push(offering_subcode);
         }
 
+        Ok((selected_results, subcodes))
+    }
+
+    async fn select_unit(&self, unit_code: &str, session: &Session, subcode: Option<&Offering>) -> Result<()> {
+        let (selected_results, subcodes) = self.offering_subcodes().await?;
+
         let first_offering = match subcodes.first() {
             Some(offering) => offering,
             None => return Err(
-                Box::new(
-                    OfferingError::NoOfferingsError(query.unit_code())
-                )
+                OfferingError::NoOfferingsError(unit_code.to_owned()).into()
             ),
         };
 
-        if subcodes.len() == 1 {
-            return match single_offering(query, first_offering) {
-                Ok(()) => {
-                    let parent = selected_results[0].parent().await?;
-                    let by = By::XPath(format_usize(OFFERING_CHECKBOX_FORMAT.as_str(), 1));
-                    let checkbox = parent.query(by).first().await?;
-                    Ok(checkbox.click().await?)
-                },
-                Err(e) => Err(e),
-            }
-        }
-        
-        match multiple_offerings(query, &subcodes) {
-            Some(index) => {
-                let parent = selected_results[0].parent().await?;
-                let by = By::XPath(format_usize(OFFERING_CHECKBOX_FORMAT.as_str(), index + 1));
-                let checkbox = parent.query(by).first().await?;
-                Ok(checkbox.click().await?)
-            }
-            None => Err(
-                Box::new(
-                    OfferingError::NoValidOfferingsError(query.unit_code())
-                )
-            )
-        }
-    }
-
-    async fn search_query(&self, show_timetable_button: &WebElement, query: &FinderQuery) -> AllocationResult {
-        show_timetable_button.click().await?;
+        let index = match subcode {
+            Some(subcode) => match pinned_offering(subcode, &subcodes) {
+                Some(index) => index,
+                None => return Err(
+                    OfferingError::SubcodeNotOffered(subcode.to_string(), subcodes).into()
+                ),
+            },
+            None if subcodes.len() == 1 => {
+                single_offering(unit_code, session, first_offering)?;
+                0
+            },
+            None => match multiple_offerings(unit_code, session, &subcodes) {
+                Some(index) => index,
+                None => return Err(
+                    OfferingError::NoValidOfferingsError(unit_code.to_owned()).into()
+                ),
+            },
+        };
 
-        let searcher = TimetableSearcher::new(&self.driver, query);
-        searcher.search().await
+        let parent = selected_results[0].parent().await?;
+        let by = By::XPath(format_usize(OFFERING_CHECKBOX_FORMAT.as_str(), index + 1));
+        let checkbox = parent.query(by).first().await?;
+        Ok(checkbox.click().await?)
     }
 
     async fn reset_timetable(&self) -> WebDriverResult<()> {
@@ -307,65 +636,221 @@ impl SeatFinder {
     }
 }
 
-pub fn run() {
-    env_logger::init();
+async fn new_driver(config: &FinderConfig) -> Result<WebDriver> {
+    let mut capabilities = DesiredCapabilities::chrome();
+    if config.headless {
+        capabilities.add_arg("--headless")?;
+    }
 
-    let rt = Runtime::new().unwrap();
+    let server_url = format!("http://localhost:{}", config.port);
+    Ok(WebDriver::new(server_url, capabilities).await?)
+}
+
+pub fn run(config_path: &Path, overrides: &ConfigOverrides) -> Result<()> {
+    let rt = Runtime::new()?;
     let start = if TIMED { Some(Instant::now()) } else { None };
     
     rt.block_on(async {
-        let seatfinder = SeatFinder::new().await;
-        seatfinder.seatfind().await;
-        seatfinder.quit().await;
-    });
+        let mut seatfinder = SeatFinder::try_new(config_path, overrides).await?;
+        let outcomes = seatfinder.seatfind().await;
+        print_summary(&outcomes);
+        seatfinder.export_calendar();
+        seatfinder.flush_notifications().await;
+        seatfinder.quit().await
+    })?;
     if let Some(instant) = start {
         info!("Program took {:.2?} seconds to execute", instant.elapsed());
     }
+    Ok(())
 }
 
-pub fn run_every(seconds: u64) {
-    env_logger::init();
-
-    let rt = Runtime::new().unwrap();
+pub fn run_every(config_path: &Path, overrides: &ConfigOverrides) -> Result<()> {
+    let rt = Runtime::new()?;
 
     rt.block_on(async {
-        let duration = Duration::from_secs(seconds);
-        let seatfinder = SeatFinder::new().await;
+        let mut seatfinder = SeatFinder::try_new(config_path, overrides).await?;
+        let mut scheduler = Scheduler::new(seatfinder.config.schedule.clone(), &seatfinder.queries);
 
-        let mut timer = time::interval(duration);
+        let mut watcher = match ConfigWatcher::new(config_path) {
+            Ok(watcher) => Some(watcher),
+            Err(e) => {
+                warn!("Could not watch {} for changes: {}", config_path.display(), e);
+                None
+            }
+        };
+
+        let mut consecutive_failures = 0;
 
         loop {
-            timer.tick().await;
+            let config_changed = async {
+                match watcher.as_mut() {
+                    Some(watcher) => watcher.changed().await,
+                    None => std::future::pending().await,
+                }
+            };
+
+            tokio::select! {
+                _ = time::sleep_until(scheduler.next_wake(time::Instant::now(), chrono::Local::now())) => {},
+                _ = config_changed => {
+                    info!("{} changed, reloading...", config_path.display());
+                    match Config::load(config_path, overrides) {
+                        Ok(config) => {
+                            seatfinder.reload(config);
+                            scheduler.reload(seatfinder.config.schedule.clone(), &seatfinder.queries);
+                        }
+                        Err(e) => error!("Refusing to apply the new config: {}", e),
+                    }
+                    continue;
+                }
+            }
 
             let now = chrono::Local::now();
+            if let Some(until) = scheduler.quiet_until(now) {
+                let formatted = format!("{}: Quiet hours until {}", now.format("[%d/%m/%y %H:%M:%S]"), until.format("%H:%M"));
+                info!("{}", formatted.cyan());
+
+                let remaining = (until - now).to_std().unwrap_or_default();
+                scheduler.defer(time::Instant::now() + remaining);
+                continue;
+            }
+
+            let due = scheduler.due(time::Instant::now(), now);
+            if due.is_empty() {
+                continue;
+            }
+
             let formatted = format!("{}: Seatfinding", now.format("[%d/%m/%y %H:%M:%S]"));
             info!("{}", formatted.red());
 
-            match seatfinder.seats_are_available().await {
-                Some(false) => continue,
+            let available = seatfinder.search(&due).await;
+            scheduler.completed(&due, available.is_some());
+            seatfinder.export_calendar();
+            if scheduler.failures() > 0 {
+                debug!("Backing off after {} failed searches", scheduler.failures());
+            }
+
+            match available {
+                Some(_) => consecutive_failures = 0,
                 None => {
+                    consecutive_failures += 1;
                     let now = chrono::Local::now();
+
+                    if consecutive_failures >= seatfinder.config.retry.max_consecutive_failures {
+                        let formatted = format!(
+                            "{}: {} consecutive failures, restarting the browser session...", 
+                            now.format("[%d/%m/%y %H:%M:%S]"),
+                            consecutive_failures
+                        );
+                        warn!("{}", formatted.cyan());
+
+                        match seatfinder.recover_session().await {
+                            Ok(()) => consecutive_failures = 0,
+                            Err(e) => error!("Error restarting the browser session: {}", e),
+                        }
+                        continue;
+                    }
+
                     let formatted = format!("{}: Refreshing page...", now.format("[%d/%m/%y %H:%M:%S]"));
                     info!("{}", formatted.cyan());
 
-                    seatfinder.driver.refresh().await.unwrap();
-                    continue;
+                    if let Err(e) = seatfinder.driver.refresh().await {
+                        error!("Error refreshing the page: {}", e);
+                    }
                 }
-                _ => {},
-            }
-
-            match seatfinder.config.music {
-                Some(ref path) => {
-                    let file_name = path
-                        .file_name()
-                        .unwrap()
-                        .to_str()
-                        .unwrap();
-                    info!("Playing '{}'", file_name);
-                    annoy(path);
-                },
-                None => info!("No music to play :("),
             }
         }
-    });
-}
\ No newline at end of file
+    })
+}
+
+pub fn check_config(config_path: &Path, overrides: &ConfigOverrides) -> Result<()> {
+    let config = Config::load(config_path, overrides)?;
+    let report = ConfigReport::new(&config);
+    report.print();
+
+    let valid = format!(
+        "{} is valid ({} queries, {} warnings)", 
+        config_path.display(), 
+        config.queries.len(), 
+        report.warnings.len()
+    );
+    println!();
+    println!("{}", valid.green());
+    Ok(())
+}
+
+pub fn list_offerings(config_path: &Path, overrides: &ConfigOverrides, unit_code: &str, choose: bool) -> Result<()> {
+    let rt = Runtime::new()?;
+
+    let subcodes = rt.block_on(async {
+        let seatfinder = SeatFinder::try_new(config_path, overrides).await?;
+        let subcodes = seatfinder.offerings(unit_code).await;
+        seatfinder.quit().await?;
+        subcodes
+    })?;
+
+    if subcodes.is_empty() {
+        info!("No offerings found for {}.", unit_code);
+        return Ok(());
+    }
+
+    listing::print_offerings(&subcodes);
+    if !choose {
+        return Ok(());
+    }
+
+    // The chosen offering is printed as a query to paste into the config
+    if let Some(offering) = listing::choose_offering(&subcodes)? {
+        let query = serde_json::json!({
+            "unit_code": offering.unit_code(),
+            "subcode": offering,
+        });
+        println!("{}", serde_json::to_string_pretty(&query)?);
+    }
+    Ok(())
+}
+
+pub fn list(config_path: &Path, overrides: &ConfigOverrides, options: &ListOptions) -> Result<()> {
+    let rt = Runtime::new()?;
+
+    let allocations = rt.block_on(async {
+        let seatfinder = SeatFinder::try_new(config_path, overrides).await?;
+        let allocations = seatfinder.allocations(&options.unit_code, &options.session, options.subcode.as_ref()).await;
+        seatfinder.quit().await?;
+        allocations
+    })?;
+
+    let mut allocations = allocations;
+    options.apply(&mut allocations);
+    if allocations.is_empty() {
+        info!("No allocations found for {}.", options.unit_code);
+    }
+
+    match options.format {
+        ReportFormat::Table => listing::print_table(&allocations),
+        ReportFormat::Csv => listing::print_csv(&allocations),
+        ReportFormat::Json => listing::print_json(&allocations)?,
+    }
+    Ok(())
+}
+
+pub fn report(config_path: &Path, overrides: &ConfigOverrides, options: &ReportOptions) -> Result<()> {
+    let history = match options.history {
+        Some(ref path) => HistoryConfig { path: path.clone(), ..HistoryConfig::default() },
+        None => Config::load(config_path, overrides)?.finder.history,
+    };
+    let store = HistoryStore::reader(history);
+
+    let mut observations = store.read()?;
+    observations.retain(|observation| options.includes(observation));
+    if observations.is_empty() {
+        info!("No matching observations are recorded in {}.", store.config().path.display());
+    }
+
+    let reports = report::summarise(&observations);
+    match options.format {
+        ReportFormat::Table => report::print_table(&reports),
+        ReportFormat::Csv => report::print_csv(&reports),
+        ReportFormat::Json => report::print_json(&reports)?,
+    }
+    Ok(())
+}
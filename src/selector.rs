@@ -6,6 +6,7 @@ pub static CLEAR_BUTTON: XPathSelector = XPathSelector(r#"//*[@id="clear-selecte
 pub static UNIT_OFFERINGS: XPathSelector = XPathSelector(r#"//*[@id="selected-results"]/li/strong"#);
 pub static OFFERING_CHECKBOX_FORMAT: XPathSelector = XPathSelector(r#"//*[@id="selected-results"]/li[{}]/input"#);
 
+pub static DAY_COLUMN_FORMAT: XPathSelector = XPathSelector(r#"//*[@id="timetable-grid"]/div[4]/div[{}]"#);
 pub static ALLOCATION_FORMAT: XPathSelector = XPathSelector(r#"//*[@id="timetable-grid"]/div[4]/div[{}]/div[{}]"#);
 pub static ALLOCATION_TABLE_ROWS: XPathSelector = XPathSelector(r#"//*[@id="activity-details-tpl"]/div[2]/div[4]/table/tbody/*"#);
 pub static GO_BACK_BUTTON: XPathSelector = XPathSelector(r#"//*[@id="activity-details-tpl"]/div[2]/div[6]/button[1]"#);
@@ -0,0 +1,263 @@
+use std::fmt;
+use std::str::FromStr;
+
+use chrono::Month;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use serde::de::{self, Unexpected, Visitor};
+
+use crate::allocation::Semester;
+use crate::consts::{SESSION_CODE_RE, SESSION_NAME_RE};
+use crate::error::ParseError;
+
+/// Part of a summer or winter school.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SessionPart {
+    Early,
+    Main,
+    Late,
+}
+
+impl SessionPart {
+    fn from_code(code: &str) -> Option<Self> {
+        match code {
+            "E" | "EARLY" => Some(Self::Early),
+            "M" | "MAIN" => Some(Self::Main),
+            "L" | "LATE" => Some(Self::Late),
+            _ => None,
+        }
+    }
+}
+
+impl fmt::Display for SessionPart {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(match self {
+            Self::Early => "early",
+            Self::Main => "main",
+            Self::Late => "late",
+        })
+    }
+}
+
+/// The teaching session of an offering, e.g. `S2C` (semester 2) or `S1CIJA` (intensive January).
+/// The summer and winter school codes that are recognised (e.g. `S1CSUMM`) have not been
+/// checked against the timetable; codes that are not recognised become [`Session::Other`].
+///
+/// In a query, a session without a month or part (e.g. `"summer"`) stands for every
+/// session of its kind.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub enum Session {
+    #[default]
+    Any,
+    /// Semester 1 or 2
+    Semester(Semester),
+    Intensive(Option<Month>),
+    Summer(Option<SessionPart>),
+    Winter(Option<SessionPart>),
+    /// A session code that is not otherwise understood, which only matches itself
+    Other(String),
+}
+
+/// The two letter month codes of intensive sessions.
+const MONTH_CODES: [(&str, Month); 12] = [
+    ("JA", Month::January),
+    ("FE", Month::February),
+    ("MR", Month::March),
+    ("AP", Month::April),
+    ("MY", Month::May),
+    ("JN", Month::June),
+    ("JL", Month::July),
+    ("AU", Month::August),
+    ("SE", Month::September),
+    ("OC", Month::October),
+    ("NO", Month::November),
+    ("DE", Month::December),
+];
+
+fn month_from_code(code: &str) -> Option<Month> {
+    MONTH_CODES.iter().find(|&&(month_code, _)| month_code == code).map(|&(_, month)| month)
+}
+
+fn month_from_name(name: &str) -> Option<Month> {
+    MONTH_CODES.iter().map(|&(_, month)| month).find(|month| {
+        let full = month.name().to_ascii_lowercase();
+        name == full || (name.len() >= 3 && full.starts_with(name))
+    })
+}
+
+impl Session {
+    /// The session of a session code as it appears in a subcode, e.g. `S2C` in
+    /// `COMP2123-S2C-ND-CC`. Codes that are not understood become [`Session::Other`].
+    pub fn from_code(code: &str) -> Self {
+        let code = code.trim().to_ascii_uppercase();
+        let Some(caps) = SESSION_CODE_RE.captures(&code) else {
+            return Self::Other(code);
+        };
+
+        let semester = caps.get(1).and_then(|semester| Semester::try_from(semester.as_str().to_owned()).ok());
+        let part = caps.get(4).and_then(|part| SessionPart::from_code(part.as_str()));
+        match (caps.get(2), caps.get(3)) {
+            (Some(month), _) => match month_from_code(month.as_str()) {
+                Some(month) => Self::Intensive(Some(month)),
+                None => Self::Other(code),
+            },
+            (None, Some(school)) if school.as_str().starts_with('S') => Self::Summer(part),
+            (None, Some(_)) => Self::Winter(part),
+            (None, None) => semester.map_or_else(|| Self::Other(code.clone()), Self::Semester),
+        }
+    }
+
+    /// The session of the text in the timetable's `Semester` column, e.g. `Semester 2`
+    /// or `Intensive January`. Text that is not understood is kept as [`Session::Other`].
+    pub fn from_label(label: &str) -> Self {
+        label.parse().unwrap_or_else(|_| Self::Other(label.trim().to_owned()))
+    }
+
+    /// Whether an offering in the session `offered` is wanted by a query for this session.
+    /// A semester only matches itself here; see [`Offering::is_in`](crate::offering::Offering::is_in)
+    /// for the sessions coded under it.
+    pub fn matches(&self, offered: &Session) -> bool {
+        match (self, offered) {
+            (Self::Any, _) => true,
+            (Self::Semester(wanted), Self::Semester(offered)) => wanted == offered,
+            (Self::Intensive(wanted), Self::Intensive(offered)) => wanted.is_none() || wanted == offered,
+            (Self::Summer(wanted), Self::Summer(offered))
+            | (Self::Winter(wanted), Self::Winter(offered)) => wanted.is_none() || wanted == offered,
+            (Self::Other(wanted), Self::Other(offered)) => wanted.eq_ignore_ascii_case(offered),
+            _ => false,
+        }
+    }
+}
+
+impl From<Semester> for Session {
+    fn from(semester: Semester) -> Self {
+        match semester {
+            Semester::Any => Self::Any,
+            semester => Self::Semester(semester),
+        }
+    }
+}
+
+impl FromStr for Session {
+    type Err = ParseError;
+
+    /// Parses a session number (`0`, `1` or `2`), a session code (e.g. `S1CIJA`) or a
+    /// name such as `semester 2`, `intensive`, `intensive july`, `summer` or `winter main`.
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let name = value.trim().to_ascii_lowercase();
+        if name == "any" {
+            return Ok(Self::Any);
+        }
+        if let Ok(semester) = name.parse::<u64>() {
+            return Semester::try_from(semester)
+                .map(Self::from)
+                .map_err(|_| ParseError::ParseSessionError(value.to_owned()));
+        }
+        // Codes are written in upper case, so e.g. `S3C` is kept as an unknown session
+        // while a misspelt name such as `sumer` is an error
+        let code = value.trim();
+        if code.len() >= 2 && code.chars().all(|c| c.is_ascii_uppercase() || c.is_ascii_digit()) {
+            return Ok(Self::from_code(code));
+        }
+        if let session @ (Self::Semester(_) | Self::Intensive(_) | Self::Summer(_) | Self::Winter(_)) =
+            Self::from_code(&name)
+        {
+            return Ok(session);
+        }
+
+        let error = || ParseError::ParseSessionError(value.to_owned());
+        let caps = SESSION_NAME_RE.captures(&name).ok_or_else(error)?;
+        let detail = caps.get(2).map(|detail| detail.as_str());
+        match (&caps[1], detail) {
+            ("semester", Some(semester)) => {
+                let semester = semester.parse::<u64>().map_err(|_| error())?;
+                match Semester::try_from(semester) {
+                    Ok(Semester::Any) | Err(_) => Err(error()),
+                    Ok(semester) => Ok(Self::Semester(semester)),
+                }
+            }
+            ("intensive", None) => Ok(Self::Intensive(None)),
+            ("intensive", Some(month)) => month_from_name(month)
+                .map(|month| Self::Intensive(Some(month)))
+                .ok_or_else(error),
+            ("summer", None) => Ok(Self::Summer(None)),
+            ("winter", None) => Ok(Self::Winter(None)),
+            (school, Some(part)) if school == "summer" || school == "winter" => {
+                let part = SessionPart::from_code(&part.to_ascii_uppercase()).ok_or_else(error)?;
+                Ok(if school == "summer" { Self::Summer(Some(part)) } else { Self::Winter(Some(part)) })
+            }
+            _ => Err(error()),
+        }
+    }
+}
+
+impl fmt::Display for Session {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Any => f.write_str("any"),
+            Self::Semester(semester) => write!(f, "semester {}", semester.clone() as u8),
+            Self::Intensive(None) => f.write_str("intensive"),
+            Self::Intensive(Some(month)) => write!(f, "intensive {}", month.name().to_ascii_lowercase()),
+            Self::Summer(None) => f.write_str("summer"),
+            Self::Summer(Some(part)) => write!(f, "summer {}", part),
+            Self::Winter(None) => f.write_str("winter"),
+            Self::Winter(Some(part)) => write!(f, "winter {}", part),
+            Self::Other(code) => f.write_str(code),
+        }
+    }
+}
+
+struct SessionVisitor {
+    /// Whether text that is not a session is kept as [`Session::Other`] rather than rejected
+    lenient: bool,
+}
+
+impl<'de> Visitor<'de> for SessionVisitor {
+    type Value = Session;
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a semester (0, 1 or 2), a session code such as \"S1CIJA\" or a session such as \"summer\"")
+    }
+
+    fn visit_u64<E: de::Error>(self, value: u64) -> Result<Self::Value, E> {
+        Semester::try_from(value)
+            .map(Session::from)
+            .map_err(|_| E::invalid_value(Unexpected::Unsigned(value), &self))
+    }
+
+    fn visit_i64<E: de::Error>(self, value: i64) -> Result<Self::Value, E> {
+        match u64::try_from(value) {
+            Ok(value) => self.visit_u64(value),
+            Err(_) => Err(E::invalid_value(Unexpected::Signed(value), &self)),
+        }
+    }
+
+    fn visit_str<E: de::Error>(self, value: &str) -> Result<Self::Value, E> {
+        if self.lenient {
+            return Ok(Session::from_label(value));
+        }
+        value.parse().map_err(|_| E::invalid_value(Unexpected::Str(value), &self))
+    }
+}
+
+impl<'de> Deserialize<'de> for Session {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        deserializer.deserialize_any(SessionVisitor { lenient: false })
+    }
+}
+
+/// Deserializes a session that was scraped from the timetable, which may be one that is not understood.
+pub fn deserialize_session_label<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Session, D::Error> {
+    deserializer.deserialize_any(SessionVisitor { lenient: true })
+}
+
+/// Semesters are written as their number, as they were before other sessions were
+/// supported, and every other session as its name.
+impl Serialize for Session {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        match self {
+            Self::Any => serializer.serialize_u8(0),
+            Self::Semester(semester) => serializer.serialize_u8(semester.clone() as u8),
+            session => serializer.collect_str(session),
+        }
+    }
+}
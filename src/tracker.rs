@@ -0,0 +1,158 @@
+use std::fmt;
+use std::time::{Duration, Instant};
+
+use serde::Deserialize;
+
+use crate::consts::DEFAULT_COOLDOWN_SECS;
+use crate::allocation::Allocation;
+use crate::query::FinderQuery;
+
+/// When to notify about a query whose seats keep being found.
+#[derive(Debug, Clone, PartialEq, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct AlertConfig {
+    /// The minimum number of seconds between two notifications for the same query
+    pub cooldown: u64,
+    /// Notify again when the number of seats rises to at least this many
+    pub threshold: Option<i16>,
+    /// Send a reminder every this many seconds while seats remain available
+    pub reminder: Option<u64>,
+}
+
+impl Default for AlertConfig {
+    fn default() -> Self {
+        Self { cooldown: DEFAULT_COOLDOWN_SECS, threshold: None, reminder: None }
+    }
+}
+
+/// A change in the seats of an allocation that is worth notifying about.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Transition {
+    /// Seats became available
+    Available { seats: i16 },
+    /// The number of seats rose to the configured threshold
+    Increased { from: i16, to: i16 },
+    /// Seats are still available after the reminder period
+    Reminder { seats: i16 },
+    /// The seats that were available have been taken
+    SoldOut,
+}
+
+impl Transition {
+    /// A short name for the transition, e.g. for webhooks and command hooks.
+    pub fn event(&self) -> &'static str {
+        match self {
+            Self::Available { .. } => "available",
+            Self::Increased { .. } => "increased",
+            Self::Reminder { .. } => "reminder",
+            Self::SoldOut => "sold_out",
+        }
+    }
+}
+
+impl fmt::Display for Transition {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Available { seats } => write!(f, "has {} seats left", seats),
+            Self::Increased { from, to } => write!(f, "now has {} seats left (up from {})", to, from),
+            Self::Reminder { seats } => write!(f, "still has {} seats left", seats),
+            Self::SoldOut => write!(f, "has no seats left"),
+        }
+    }
+}
+
+#[derive(Debug)]
+struct QueryState {
+    query: FinderQuery,
+    activity: u64,
+    /// The number of seats at the time of the last notification
+    announced: i16,
+    notified_at: Option<Instant>,
+}
+
+/// Remembers the seats of every allocation matching each query across polls so that
+/// a notification is only sent when they change, rather than on every poll they are available.
+#[derive(Debug, Default)]
+pub struct SeatTracker {
+    config: AlertConfig,
+    states: Vec<QueryState>,
+}
+
+impl SeatTracker {
+    pub fn new(config: AlertConfig) -> Self {
+        Self { config, states: Vec::new() }
+    }
+
+    /// Applies a new config, forgetting queries that are no longer searched for.
+    pub fn reload(&mut self, config: AlertConfig, queries: &[FinderQuery]) {
+        self.config = config;
+        self.states.retain(|state| queries.contains(&state.query));
+    }
+
+    /// Records the seats of an allocation found for `query`, returning the transition
+    /// to notify about (if any). Fewer seats than the query's `min_seats` count as none.
+    pub fn observe(&mut self, query: &FinderQuery, allocation: &Allocation) -> Option<Transition> {
+        self.observe_at(query, allocation, Instant::now())
+    }
+
+    /// Like [`observe`](Self::observe), as if the allocation was found at `now`.
+    pub fn observe_at(&mut self, query: &FinderQuery, allocation: &Allocation, now: Instant) -> Option<Transition> {
+        let seats = if query.has_seats(allocation) { allocation.seats } else { 0 };
+        let position = self.states
+            .iter()
+            .position(|state| state.query == *query && state.activity == allocation.activity);
+
+        let index = match position {
+            Some(index) => index,
+            None => {
+                self.states.push(QueryState { 
+                    query: query.clone(), 
+                    activity: allocation.activity, 
+                    announced: 0, 
+                    notified_at: None 
+                });
+                self.states.len() - 1
+            }
+        };
+
+        let state = &mut self.states[index];
+        let elapsed = state.notified_at.map(|notified_at| now.duration_since(notified_at));
+
+        let transition = transition(&self.config, state.announced, seats, elapsed)?;
+        let cooling_down = elapsed.is_some_and(|elapsed| elapsed < Duration::from_secs(self.config.cooldown));
+        if cooling_down {
+            // The state is kept so that the transition is reported once the cooldown
+            // is over, unless the seats change back in the meantime
+            return None;
+        }
+
+        state.announced = seats;
+        state.notified_at = Some(now);
+        Some(transition)
+    }
+
+    /// Forgets the seats of an allocation, so that they are notified about again when next observed.
+    pub fn forget(&mut self, query: &FinderQuery, allocation: &Allocation) {
+        self.states.retain(|state| state.query != *query || state.activity != allocation.activity);
+    }
+}
+
+fn transition(config: &AlertConfig, announced: i16, seats: i16, elapsed: Option<Duration>) -> Option<Transition> {
+    if announced == 0 {
+        return (seats > 0).then_some(Transition::Available { seats });
+    }
+    if seats == 0 {
+        return Some(Transition::SoldOut);
+    }
+
+    if let Some(threshold) = config.threshold {
+        if announced < threshold && seats >= threshold {
+            return Some(Transition::Increased { from: announced, to: seats });
+        }
+    }
+
+    let reminder = config.reminder.map(Duration::from_secs)?;
+    elapsed
+        .is_some_and(|elapsed| elapsed >= reminder)
+        .then_some(Transition::Reminder { seats })
+}
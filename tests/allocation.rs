@@ -0,0 +1,46 @@
+use seatfinder::allocation::{ActivityDuration, TeachingWeeks, TwentyFourHourTime, WeekSet};
+
+#[test]
+fn parses_durations() {
+    let minutes = |raw: &str| ActivityDuration::new(raw).minutes();
+
+    assert_eq!(minutes("1 hr"), Some(60));
+    assert_eq!(minutes("2 hrs"), Some(120));
+    assert_eq!(minutes("1 hr 30 mins"), Some(90));
+    assert_eq!(minutes("1.5 hours"), Some(90));
+    assert_eq!(minutes("50 mins"), Some(50));
+    assert_eq!(minutes("1HR 30MINS"), Some(90));
+    assert_eq!(minutes("2"), None);
+    assert_eq!(minutes("1:30"), None);
+    assert_eq!(minutes("1 hr 30"), None);
+    assert_eq!(minutes("1 hrs2"), None);
+    assert_eq!(minutes("1 fortnight"), None);
+    assert_eq!(minutes(""), None);
+    assert_eq!(ActivityDuration::new("1 hr 30 mins").to_string(), "1 hr 30 mins");
+}
+
+#[test]
+fn parses_weeks() {
+    let weeks = TeachingWeeks::new("1-6, 8-13");
+    let set = weeks.weeks().unwrap();
+
+    assert!(set.contains(1) && set.contains(6) && set.contains(8) && set.contains(13));
+    assert!(!set.contains(7) && !set.contains(14));
+    assert_eq!(set.to_string(), "1-6, 8-13");
+    assert_eq!(weeks.to_string(), "1-6, 8-13");
+
+    assert_eq!("2,4, 5,6".parse::<WeekSet>().unwrap().to_string(), "2, 4-6");
+    assert!("6-1".parse::<WeekSet>().is_err());
+    assert!("weekly".parse::<WeekSet>().is_err());
+    assert!(TeachingWeeks::new("weekly").weeks().is_none());
+    assert_eq!(TeachingWeeks::new("weekly").to_string(), "weekly");
+}
+
+#[test]
+fn works_out_times_of_day() {
+    let time = TwentyFourHourTime::new("1:30pm").unwrap();
+
+    assert_eq!(time.minutes_since_midnight(), 13 * 60 + 30);
+    assert_eq!(TwentyFourHourTime::from_minutes_since_midnight(13 * 60 + 30), Some(time));
+    assert_eq!(TwentyFourHourTime::from_minutes_since_midnight(24 * 60), None);
+}
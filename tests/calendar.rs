@@ -0,0 +1,91 @@
+use chrono::NaiveDate;
+
+use seatfinder::allocation::Allocation;
+use seatfinder::calendar::{Calendar, CalendarConfig, CalendarEntry};
+
+mod common;
+
+fn config() -> CalendarConfig {
+    CalendarConfig {
+        path: "allocations.ics".into(),
+        semester_start: NaiveDate::from_ymd_opt(2024, 7, 29).unwrap(),
+        mid_semester_break: Some(7),
+    }
+}
+
+fn entry(allocation: Allocation) -> CalendarEntry {
+    CalendarEntry { unit_code: "COMP2123".to_owned(), allocation }
+}
+
+/// The content lines of an event, with folded lines joined back together.
+fn unfolded(event: &str) -> Vec<String> {
+    event.replace("\r\n ", "").split("\r\n").map(str::to_owned).collect()
+}
+
+fn line<'a>(lines: &'a [String], name: &str) -> &'a str {
+    let prefix = format!("{}:", name);
+    lines.iter().find_map(|line| line.strip_prefix(&prefix)).unwrap()
+}
+
+#[test]
+fn excludes_skipped_weeks_and_the_break() {
+    let config = config();
+    let event = Calendar::new(&config).event(&entry(common::allocation().weeks("2-5, 7-9").build())).unwrap();
+    let lines = unfolded(&event);
+
+    assert_eq!(line(&lines, "DTSTART"), "20240806T120000");
+    assert_eq!(line(&lines, "DTEND"), "20240806T130000");
+    assert_eq!(line(&lines, "RRULE"), "FREQ=WEEKLY;UNTIL=20241001T120000");
+    // Week 6 is skipped and the break falls after week 7
+    assert_eq!(line(&lines, "EXDATE"), "20240903T120000,20240917T120000");
+
+    let event = Calendar::new(&config).event(&entry(common::allocation().weeks("1-5").build())).unwrap();
+    assert!(!unfolded(&event).iter().any(|line| line.starts_with("EXDATE")));
+}
+
+#[test]
+fn escapes_text() {
+    let config = config();
+    let allocation = common::allocation()
+        .description("Tutorial\nBring a laptop")
+        .location("Room 1, Level 2; A\\B")
+        .weeks("1-5")
+        .build();
+    let event = Calendar::new(&config).event(&entry(allocation)).unwrap();
+    let lines = unfolded(&event);
+
+    assert_eq!(line(&lines, "LOCATION"), "Room 1\\, Level 2\\; A\\\\B");
+    assert_eq!(
+        line(&lines, "DESCRIPTION"),
+        "Tutorial\\nBring a laptop\\nGroup: TUT\\nWeeks: 1-5\\nCampus: Camperdown/Darlington\\, Sydney"
+    );
+    assert_eq!(line(&lines, "SUMMARY"), "COMP2123 Tutorial 1");
+}
+
+#[test]
+fn folds_lines_at_75_octets() {
+    let config = config();
+    let calendar = Calendar::new(&config);
+    let location_line = |location: &str| -> Vec<String> {
+        let event = calendar.event(&entry(common::allocation().location(location).build())).unwrap();
+        let start = event.find("LOCATION:").unwrap();
+        let end = event[start..].find("\r\nDESCRIPTION:").unwrap();
+        event[start..start + end].split("\r\n").map(str::to_owned).collect()
+    };
+
+    // "LOCATION:" is 9 octets, so 66 more fill the line exactly
+    let exact = "a".repeat(66);
+    assert_eq!(location_line(&exact), vec![format!("LOCATION:{}", exact)]);
+
+    let over = "a".repeat(67);
+    assert_eq!(location_line(&over), vec![format!("LOCATION:{}", "a".repeat(66)), " a".to_owned()]);
+
+    // A two octet character that does not fit is moved to the next line whole
+    let multibyte = format!("{}é{}", "a".repeat(65), "ü".repeat(40));
+    let lines = location_line(&multibyte);
+    assert_eq!(lines[0], format!("LOCATION:{}", "a".repeat(65)));
+    assert!(lines.iter().all(|line| line.len() <= 75));
+    assert_eq!(lines[1].len(), 75);
+    assert!(lines[1..].iter().all(|line| line.starts_with(' ')));
+    assert_eq!(lines.join("\r\n").replace("\r\n ", ""), format!("LOCATION:{}", multibyte));
+}
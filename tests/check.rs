@@ -0,0 +1,86 @@
+use seatfinder::check::{ConfigReport, ConfigWarning};
+use seatfinder::config::Config;
+use serde_json::{json, Value};
+
+/// A config with one query for tutorials in the second semester, where the
+/// query and the config itself are extended by the given fields.
+fn parse(query: Value, fields: Value) -> Config {
+    let mut config = json!({
+        "query": { "unit_code": "COMP2123", "day": 2, "activity_type": "Tutorial", "semester": 2 }
+    });
+    config["query"].as_object_mut().unwrap().extend(query.as_object().unwrap().clone());
+    config.as_object_mut().unwrap().extend(fields.as_object().unwrap().clone());
+    config.to_string().parse().unwrap()
+}
+
+fn warnings(config: &Config) -> Vec<String> {
+    ConfigReport::new(config).warnings.iter().map(ToString::to_string).collect()
+}
+
+#[test]
+fn does_not_warn_about_a_sound_config() {
+    let config = parse(json!({ "start_after": "22:00", "start_before": "23:00" }), json!({
+        "notifiers": { "alarm": { "type": "audio", "path": "Cargo.toml" } },
+        "planner": { "commitments": [{ "name": "Work", "day": 2, "start": "12:00", "end": "14:00" }] }
+    }));
+    assert!(warnings(&config).is_empty(), "{:?}", warnings(&config));
+
+    // A subcode picks the offering even without a semester
+    let config = parse(json!({ "semester": 0, "subcode": "COMP2123-S2C-ND-CC" }), json!({}));
+    assert!(warnings(&config).is_empty(), "{:?}", warnings(&config));
+}
+
+#[test]
+fn warns_about_an_ambiguous_semester() {
+    let config = parse(json!({ "semester": 0 }), json!({}));
+    let report = ConfigReport::new(&config);
+    assert!(
+        matches!(report.warnings[..], [ConfigWarning::AmbiguousSemester { query: 1, ref unit_code }] if unit_code == "COMP2123"),
+        "{:?}",
+        report.warnings
+    );
+}
+
+#[test]
+fn warns_about_start_times_the_timetable_cannot_filter_by() {
+    for start_after in ["22:01", "23:30"] {
+        let config = parse(json!({ "start_after": start_after }), json!({}));
+        let report = ConfigReport::new(&config);
+        assert!(matches!(report.warnings[..], [ConfigWarning::StartAfterOverflow { query: 1, .. }]), "{:?}", report.warnings);
+    }
+
+    let config = parse(json!({ "start_after": "22:01" }), json!({}));
+    assert_eq!(
+        warnings(&config),
+        ["query 1: start_after 22:01 is later than 22:00, so the timetable cannot filter by it when it is entered one hour later"]
+    );
+}
+
+#[test]
+fn warns_about_an_empty_time_range() {
+    let config = parse(json!({ "start_after": "14:00", "start_before": "13:00" }), json!({}));
+    let report = ConfigReport::new(&config);
+    assert!(matches!(report.warnings[..], [ConfigWarning::EmptyTimeRange { query: 1, .. }]), "{:?}", report.warnings);
+
+    // An activity starting at exactly that time still matches
+    let config = parse(json!({ "start_after": "14:00", "start_before": "14:00" }), json!({}));
+    assert!(warnings(&config).is_empty(), "{:?}", warnings(&config));
+}
+
+#[test]
+fn warns_about_a_missing_audio_file() {
+    let config = parse(json!({}), json!({
+        "notifiers": { "alarm": { "type": "audio", "path": "does/not/exist.mp3" } }
+    }));
+    assert_eq!(warnings(&config), ["notifier \"alarm\": audio file does/not/exist.mp3 does not exist"]);
+}
+
+#[test]
+fn warns_about_an_empty_commitment() {
+    for end in ["12:00", "11:00"] {
+        let config = parse(json!({}), json!({
+            "planner": { "commitments": [{ "name": "Work", "day": 2, "start": "12:00", "end": end }] }
+        }));
+        assert_eq!(warnings(&config), ["commitment \"Work\" does not end after it starts, so nothing can clash with it"]);
+    }
+}
@@ -0,0 +1,104 @@
+//! Fixtures shared by the integration tests.
+#![allow(dead_code)]
+
+use chrono::{DateTime, Local};
+use serde::de::DeserializeOwned;
+use serde_json::Value;
+
+use seatfinder::allocation::{
+    ActivityDuration,
+    ActivityType,
+    Allocation,
+    Day,
+    Semester,
+    TeachingWeeks,
+    TwentyFourHourTime
+};
+use seatfinder::history::Observation;
+
+/// Deserializes a config or query from JSON, e.g. an `AlertConfig` or a `FinderQuery`.
+pub fn config<T: DeserializeOwned>(value: Value) -> T {
+    serde_json::from_value(value).unwrap()
+}
+
+/// An allocation of tutorial 1 on Tuesdays at 12:00 for an hour in weeks 2-13, with one seat left.
+pub fn allocation() -> AllocationBuilder {
+    AllocationBuilder(Allocation {
+        activity_type: ActivityType::Tutorial,
+        group: "TUT".to_owned(),
+        activity: 1,
+        description: "Tutorial".to_owned(),
+        day: Day::Tuesday,
+        time: TwentyFourHourTime::new("12:00").unwrap(),
+        semester: Semester::Two.into(),
+        campus: "Camperdown/Darlington, Sydney".to_owned(),
+        location: "Carslaw 173".to_owned(),
+        duration: ActivityDuration::new("1 hr"),
+        weeks: TeachingWeeks::new("2-13"),
+        seats: 1,
+    })
+}
+
+/// An observation of a tutorial of COMP2123 on Tuesdays at 12:00 in the second semester.
+pub fn observation(activity: u64, seats: i16, timestamp: DateTime<Local>) -> Observation {
+    Observation {
+        timestamp,
+        unit_code: "COMP2123".to_owned(),
+        activity_type: ActivityType::Tutorial,
+        activity,
+        seats,
+        semester: Semester::Two.into(),
+        day: Day::Tuesday,
+        time: TwentyFourHourTime::new("12:00").unwrap(),
+        location: "Carslaw 173".to_owned(),
+    }
+}
+
+/// Changes the fields of an [`allocation`] that a test is about.
+pub struct AllocationBuilder(Allocation);
+
+impl AllocationBuilder {
+    pub fn activity(mut self, activity: u64) -> Self {
+        self.0.activity = activity;
+        self
+    }
+
+    pub fn description(mut self, description: &str) -> Self {
+        self.0.description = description.to_owned();
+        self
+    }
+
+    pub fn day(mut self, day: Day) -> Self {
+        self.0.day = day;
+        self
+    }
+
+    pub fn time(mut self, time: &str) -> Self {
+        self.0.time = TwentyFourHourTime::new(time).unwrap();
+        self
+    }
+
+    pub fn location(mut self, location: &str) -> Self {
+        self.0.location = location.to_owned();
+        self
+    }
+
+    pub fn duration(mut self, duration: &str) -> Self {
+        self.0.duration = ActivityDuration::new(duration);
+        self
+    }
+
+    pub fn weeks(mut self, weeks: &str) -> Self {
+        self.0.weeks = TeachingWeeks::new(weeks);
+        self
+    }
+
+    pub fn seats(mut self, seats: i16) -> Self {
+        self.0.seats = seats;
+        self
+    }
+
+    pub fn build(self) -> Allocation {
+        self.0
+    }
+}
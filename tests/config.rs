@@ -0,0 +1,73 @@
+use seatfinder::config::{Config, ConfigFormat};
+use seatfinder::query::ConfigOverrides;
+
+const JSON: &str = r#"{
+    "headless": true,
+    "queries": [
+        { "unit_code": "COMP2123", "day": 2, "activity_type": "Tutorial", "activity": 1 },
+        {
+            "unit_code": "INFO1110",
+            "day": 9,
+            "activity_type": "Tutorial",
+            "activity": 4
+        }
+    ]
+}
+"#;
+
+const TOML: &str = r#"headless = true
+
+# The second query is on a day that does not exist
+[[queries]]
+unit_code = "COMP2123"
+day = 2
+activity_type = "Tutorial"
+activity = 1
+
+[[queries]]
+unit_code = "INFO1110"
+day = 9
+activity_type = "Tutorial"
+activity = 4
+"#;
+
+const YAML: &str = r#"headless: true
+queries:
+  - unit_code: COMP2123
+    day: 2
+    activity_type: Tutorial
+    activity: 1
+  - unit_code: INFO1110
+    day: 9
+    activity_type: Tutorial
+    activity: 4
+"#;
+
+/// The message of the error from parsing `contents`.
+fn parse_error(contents: &str, format: ConfigFormat) -> String {
+    Config::parse(contents, format, &ConfigOverrides::default()).unwrap_err().to_string()
+}
+
+#[test]
+fn reports_where_a_bad_field_is() {
+    for (contents, format, line) in [(JSON, ConfigFormat::Json, 7), (TOML, ConfigFormat::Toml, 12), (YAML, ConfigFormat::Yaml, 8)] {
+        let error = parse_error(contents, format);
+        assert!(error.starts_with("invalid config at `queries[1].day`: "), "{}", error);
+        assert!(error.contains(&format!(" at line {} column ", line)), "{}", error);
+    }
+
+    // TOML errors only carry a byte span, from which the column is counted in characters
+    let error = parse_error(TOML, ConfigFormat::Toml);
+    assert!(error.contains(" at line 12 column 7"), "{}", error);
+    let error = parse_error("# Ünïcödé\nheadless = \"é\"\n", ConfigFormat::Toml);
+    assert!(error.starts_with("invalid config at `headless`: "), "{}", error);
+    assert!(error.contains(" at line 2 column 12"), "{}", error);
+}
+
+#[test]
+fn parses_each_format() {
+    for (contents, format) in [(JSON, ConfigFormat::Json), (TOML, ConfigFormat::Toml), (YAML, ConfigFormat::Yaml)] {
+        let config = Config::parse(&contents.replace('9', "3"), format, &ConfigOverrides::default()).unwrap();
+        assert_eq!(config.queries.len(), 2, "{:?}", format);
+    }
+}
@@ -0,0 +1,82 @@
+use seatfinder::group::{Alternative, GroupResolver, QueryGroup, Resolution};
+use seatfinder::query::FinderQuery;
+use seatfinder::tracker::Transition;
+use serde_json::json;
+
+/// Tutorial 3, otherwise tutorial 5, otherwise tutorial 7.
+fn tutorials() -> Vec<FinderQuery> {
+    let alternatives = [3, 5, 7].map(|activity| json!({
+        "unit_code": "COMP2123", "day": 2, "activity_type": "Tutorial", "activity": activity
+    }));
+    let group: QueryGroup = serde_json::from_value(json!({ "name": "tutorials", "alternatives": alternatives })).unwrap();
+    group.into_queries()
+}
+
+fn alternatives(queries: &[FinderQuery]) -> Vec<Alternative> {
+    queries.iter().map(|query| query.alternative.clone().unwrap()).collect()
+}
+
+#[test]
+fn prefers_earlier_alternatives() {
+    let queries = tutorials();
+    let [first, second, third] = <[Alternative; 3]>::try_from(alternatives(&queries)).unwrap();
+    let mut resolver = GroupResolver::new(&queries);
+    assert_eq!(resolver.best("tutorials"), None);
+
+    resolver.update(&second, true);
+    resolver.update(&third, true);
+    assert_eq!(resolver.best("tutorials"), Some(2));
+    assert_eq!(resolver.resolve(&second, Transition::Available { seats: 2 }), Resolution::Notify { better_than: None });
+    assert_eq!(resolver.resolve(&third, Transition::Available { seats: 4 }), Resolution::Suppress);
+
+    // A more preferred alternative notes the one it replaces
+    resolver.update(&first, true);
+    assert_eq!(resolver.best("tutorials"), Some(1));
+    assert_eq!(resolver.resolve(&first, Transition::Available { seats: 1 }), Resolution::Notify { better_than: Some(2) });
+    assert_eq!(resolver.resolve(&second, Transition::Reminder { seats: 2 }), Resolution::Suppress);
+    assert_eq!(resolver.resolve(&third, Transition::Increased { from: 4, to: 6 }), Resolution::Suppress);
+}
+
+#[test]
+fn falls_back_when_the_preferred_alternative_sells_out() {
+    let queries = tutorials();
+    let [first, second, third] = <[Alternative; 3]>::try_from(alternatives(&queries)).unwrap();
+    let mut resolver = GroupResolver::new(&queries);
+
+    for alternative in [&first, &second] {
+        resolver.update(alternative, true);
+    }
+    assert_eq!(resolver.resolve(&first, Transition::Available { seats: 1 }), Resolution::Notify { better_than: None });
+    assert_eq!(resolver.resolve(&second, Transition::Available { seats: 2 }), Resolution::Suppress);
+
+    // Only the alternative that was matched selling out is of interest
+    resolver.update(&third, false);
+    assert_eq!(resolver.resolve(&third, Transition::SoldOut), Resolution::Suppress);
+
+    resolver.update(&first, false);
+    assert_eq!(resolver.best("tutorials"), Some(2));
+    assert_eq!(resolver.resolve(&first, Transition::SoldOut), Resolution::Notify { better_than: None });
+    assert_eq!(resolver.resolve(&second, Transition::Reminder { seats: 2 }), Resolution::Notify { better_than: None });
+
+    resolver.update(&second, false);
+    assert_eq!(resolver.best("tutorials"), None);
+    assert_eq!(resolver.resolve(&second, Transition::SoldOut), Resolution::Notify { better_than: None });
+}
+
+#[test]
+fn forgets_groups_that_are_no_longer_searched_for() {
+    let queries = tutorials();
+    let second = alternatives(&queries)[1].clone();
+    let mut resolver = GroupResolver::new(&queries);
+
+    resolver.update(&second, true);
+    resolver.reload(&queries);
+    assert_eq!(resolver.best("tutorials"), Some(2));
+
+    resolver.reload(&[]);
+    assert_eq!(resolver.best("tutorials"), None);
+    assert_eq!(resolver.resolve(&second, Transition::Available { seats: 2 }), Resolution::Notify { better_than: None });
+
+    resolver.reload(&queries);
+    assert_eq!(resolver.best("tutorials"), None);
+}
@@ -0,0 +1,128 @@
+use std::fs;
+use std::path::PathBuf;
+
+use chrono::{Duration, Local};
+use serde_json::json;
+
+use seatfinder::allocation::{ActivityType, Semester};
+use seatfinder::session::Session;
+use seatfinder::history::{seat_changes, AllocationKey, HistoryConfig, HistoryStore, Observation};
+use seatfinder::query::FinderQuery;
+use seatfinder::report::{summarise, Trend};
+
+mod common;
+
+fn history_path(name: &str) -> PathBuf {
+    let directory = std::env::temp_dir().join(format!("seatfinder-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&directory);
+    directory.join("history.jsonl")
+}
+
+fn observation(activity: u64, seats: i16, age: Duration) -> Observation {
+    common::observation(activity, seats, Local::now() - age)
+}
+
+#[test]
+fn reads_back_observations_per_query_and_allocation() {
+    let path = history_path("read");
+    let config = HistoryConfig { path: path.clone(), ..HistoryConfig::default() };
+    let mut store = HistoryStore::open(config).unwrap();
+
+    for (activity, seats) in [(4, 0), (5, 1), (4, 2), (4, 2), (4, 0)] {
+        store.record(&observation(activity, seats, Duration::zero())).unwrap();
+    }
+    fs::write(&path, fs::read_to_string(&path).unwrap() + "{\"truncated\n").unwrap();
+
+    assert_eq!(store.read().unwrap().len(), 5);
+
+    let query: FinderQuery = serde_json::from_value(json!({
+        "unit_code": "COMP2123",
+        "day": "Tuesday",
+        "activity_type": "Tutorial",
+        "activity": 4
+    }))
+    .unwrap();
+    let seats: Vec<i16> = store.for_query(&query).unwrap().iter().map(|o| o.seats).collect();
+    assert_eq!(seats, vec![0, 2, 2, 0]);
+
+    let key = AllocationKey {
+        unit_code: "COMP2123".to_owned(),
+        semester: Semester::Two.into(),
+        activity_type: ActivityType::Tutorial,
+        activity: 5
+    };
+    assert_eq!(store.for_allocation(&key).unwrap().len(), 1);
+
+    let observations = store.for_query(&query).unwrap();
+    let changes: Vec<i16> = seat_changes(&observations).iter().map(|o| o.seats).collect();
+    assert_eq!(changes, vec![0, 2, 0]);
+}
+
+#[test]
+fn keeps_sessions_of_a_unit_apart() {
+    let path = history_path("sessions");
+    let mut store = HistoryStore::open(HistoryConfig { path, ..HistoryConfig::default() }).unwrap();
+
+    let first: Session = Semester::One.into();
+    for (semester, seats) in [(&first, 3), (&Semester::Two.into(), 0), (&first, 3), (&Semester::Two.into(), 2)] {
+        store.record(&Observation { semester: semester.clone(), ..observation(4, seats, Duration::zero()) }).unwrap();
+    }
+
+    let query: FinderQuery = common::config(json!({
+        "unit_code": "COMP2123", "semester": 2, "day": "Tuesday", "activity_type": "Tutorial", "activity": 4
+    }));
+    let observations = store.for_query(&query).unwrap();
+    let seats: Vec<i16> = observations.iter().map(|o| o.seats).collect();
+    assert_eq!(seats, vec![0, 2]);
+
+    let key = AllocationKey { unit_code: "COMP2123".to_owned(), semester: first, activity_type: ActivityType::Tutorial, activity: 4 };
+    assert_eq!(store.for_allocation(&key).unwrap().len(), 2);
+
+    // The seats of one session do not count as a change of the other's
+    let observations = store.read().unwrap();
+    assert_eq!(seat_changes(&observations).len(), 3);
+    let reports = summarise(&observations);
+    assert_eq!(reports.iter().map(|report| report.observations).collect::<Vec<_>>(), vec![2, 2]);
+}
+
+#[test]
+fn prunes_past_retention_limits() {
+    let path = history_path("prune");
+    let config = HistoryConfig { path: path.clone(), max_age: None, ..HistoryConfig::default() };
+    let mut store = HistoryStore::open(config).unwrap();
+
+    store.record(&observation(1, 0, Duration::days(40))).unwrap();
+    store.record(&observation(2, 0, Duration::days(20))).unwrap();
+    store.record(&observation(3, 0, Duration::days(10))).unwrap();
+    store.record(&observation(4, 0, Duration::zero())).unwrap();
+
+    let config = HistoryConfig { path: path.clone(), max_age: Some(30), max_entries: Some(2), ..HistoryConfig::default() };
+    let store = HistoryStore::open(config).unwrap();
+
+    let activities: Vec<u64> = store.read().unwrap().iter().map(|o| o.activity).collect();
+    assert_eq!(activities, vec![3, 4]);
+}
+
+#[test]
+fn summarises_openings_and_trend() {
+    let start = Local::now() - Duration::hours(10);
+    let observations: Vec<Observation> = [0, 3, 2, 0, 0, 1, 4]
+        .into_iter()
+        .enumerate()
+        .map(|(hour, seats)| Observation {
+            timestamp: start + Duration::hours(hour as i64),
+            ..observation(4, seats, Duration::zero())
+        })
+        .collect();
+
+    let reports = summarise(&observations);
+    assert_eq!(reports.len(), 1);
+
+    let report = &reports[0];
+    assert_eq!(report.observations, 7);
+    assert_eq!(report.openings, 2);
+    assert_eq!(report.average_open_secs, Some(2 * 3600));
+    assert_eq!(report.openings_by_hour.iter().sum::<usize>(), 2);
+    assert_eq!(report.current_seats, 4);
+    assert_eq!(report.trend, Trend::Rising);
+}
@@ -0,0 +1,89 @@
+use serde_json::{json, Value};
+
+use seatfinder::allocation::{ActivityType, Allocation, Day};
+use seatfinder::listing::{self, ListOptions, ListSort};
+use seatfinder::report::ReportFormat;
+
+mod common;
+
+fn options(sort: ListSort) -> ListOptions {
+    ListOptions {
+        unit_code: "COMP2123".to_owned(),
+        session: Default::default(),
+        subcode: None,
+        activity_type: None,
+        sort,
+        format: ReportFormat::Table,
+    }
+}
+
+/// Tutorials 1 to 4 on different days and times, two of which have as many seats left.
+fn allocations() -> Vec<Allocation> {
+    vec![
+        common::allocation().activity(1).day(Day::Wednesday).time("09:00").seats(3).build(),
+        common::allocation().activity(2).day(Day::Monday).time("14:00").seats(5).build(),
+        common::allocation().activity(3).day(Day::Tuesday).time("09:00").seats(3).build(),
+        common::allocation().activity(4).day(Day::Monday).time("11:00").seats(0).build(),
+    ]
+}
+
+fn sorted(sort: ListSort) -> Vec<u64> {
+    let mut allocations = allocations();
+    options(sort).apply(&mut allocations);
+    allocations.iter().map(|allocation| allocation.activity).collect()
+}
+
+#[test]
+fn sorts_by_day_time_or_seats() {
+    assert_eq!(sorted(ListSort::Day), [4, 2, 3, 1]);
+    assert_eq!(sorted(ListSort::Time), [3, 1, 4, 2]);
+    // Allocations with as many seats left are ordered by day and time
+    assert_eq!(sorted(ListSort::Seats), [2, 3, 1, 4]);
+}
+
+#[test]
+fn only_lists_the_given_activity_type() {
+    let mut allocations = allocations();
+    allocations[1].activity_type = ActivityType::Lecture;
+
+    let options = ListOptions { activity_type: Some(ActivityType::Lecture), ..options(ListSort::Day) };
+    options.apply(&mut allocations);
+    assert_eq!(allocations.len(), 1);
+    assert_eq!(allocations[0].activity, 2);
+}
+
+#[test]
+fn writes_csv() {
+    let allocations = [
+        common::allocation().build(),
+        common::allocation().activity(2).description("Tutorial \"B\"").location("Room 1, Level 2").build(),
+    ];
+    assert_eq!(
+        listing::csv(&allocations),
+        "day,time,duration,activity_type,group,activity,description,seats,weeks,campus,location\n\
+        Tuesday,12:00,1 hr,Tutorial,TUT,1,Tutorial,1,2-13,\"Camperdown/Darlington, Sydney\",Carslaw 173\n\
+        Tuesday,12:00,1 hr,Tutorial,TUT,2,\"Tutorial \"\"B\"\"\",1,2-13,\"Camperdown/Darlington, Sydney\",\"Room 1, Level 2\"\n"
+    );
+    assert_eq!(listing::csv(&[]).lines().count(), 1);
+}
+
+#[test]
+fn writes_json() {
+    let allocations = [common::allocation().seats(4).build()];
+    let value: Value = serde_json::from_str(&listing::json(&allocations).unwrap()).unwrap();
+    assert_eq!(value, json!([{
+        "activity_type": "Tutorial",
+        "group": "TUT",
+        "activity": 1,
+        "description": "Tutorial",
+        "day": "Tuesday",
+        "time": "12:00",
+        "semester": 2,
+        "campus": "Camperdown/Darlington, Sydney",
+        "location": "Carslaw 173",
+        "duration": "1 hr",
+        "weeks": "2-13",
+        "seats": 4
+    }]));
+    assert_eq!(listing::json(&[]).unwrap(), "[]");
+}
@@ -0,0 +1,58 @@
+use std::io;
+
+use seatfinder::error::Error;
+use seatfinder::outcome::{FindStep, QueryOutcome};
+use seatfinder::query::FinderQuery;
+use serde_json::json;
+
+mod common;
+
+fn reset_failed() -> Result<(), Error> {
+    Err(io::Error::other("the clear button is missing").into())
+}
+
+#[test]
+fn keeps_scraped_allocations_when_the_reset_fails() {
+    let found = QueryOutcome::Found(vec![common::allocation().seats(2).build()]);
+    let (outcome, unreset) = found.after_reset(reset_failed());
+    assert!(matches!(outcome, QueryOutcome::Found(ref allocations) if allocations[0].seats == 2));
+    assert!(unreset.is_some());
+
+    let no_seats = QueryOutcome::NoSeats(vec![common::allocation().seats(0).build()]);
+    let (outcome, unreset) = no_seats.after_reset(reset_failed());
+    assert!(matches!(outcome, QueryOutcome::NoSeats(_)));
+    assert!(unreset.is_some());
+
+    let (outcome, unreset) = QueryOutcome::NotFound.after_reset(Ok(()));
+    assert!(matches!(outcome, QueryOutcome::NotFound));
+    assert!(unreset.is_none());
+}
+
+#[test]
+fn keeps_the_cause_of_a_failed_search() {
+    let failed = QueryOutcome::Error { step: FindStep::SearchQuery, cause: io::Error::other("timed out").into() };
+    let (outcome, unreset) = failed.after_reset(reset_failed());
+
+    match outcome {
+        QueryOutcome::Error { step, cause } => {
+            assert_eq!(step, FindStep::SearchQuery);
+            assert!(cause.to_string().contains("timed out"), "{}", cause);
+        }
+        outcome => panic!("expected an error, got {:?}", outcome),
+    }
+    assert!(unreset.is_none());
+}
+
+#[test]
+fn summarises_allocations_with_enough_seats_for_the_query() {
+    let query: FinderQuery = common::config(json!({
+        "unit_code": "COMP2123", "day": 2, "activity_type": "Tutorial", "min_seats": 3
+    }));
+    let found = QueryOutcome::Found(vec![
+        common::allocation().activity(1).seats(2).build(),
+        common::allocation().activity(2).seats(3).build(),
+        common::allocation().activity(3).seats(5).build(),
+    ]);
+    assert_eq!(found.summary(&query), "found activity 2 (3 seats left), activity 3 (5 seats left)");
+    assert_eq!(QueryOutcome::NoSeats(Vec::new()).summary(&query), "no seats left");
+}
@@ -0,0 +1,70 @@
+use seatfinder::allocation::Allocation;
+use seatfinder::planner::{Clash, Planner};
+use seatfinder::query::FinderQuery;
+use serde_json::json;
+
+mod common;
+
+fn query(unit_code: &str) -> FinderQuery {
+    common::config(json!({ "unit_code": unit_code, "day": 2, "activity_type": "Tutorial" }))
+}
+
+/// A Tuesday allocation at `time`.
+fn allocation(activity: u64, time: &str, duration: &str, weeks: &str) -> Allocation {
+    common::allocation().activity(activity).time(time).duration(duration).weeks(weeks).build()
+}
+
+#[test]
+fn touching_allocations_do_not_clash() {
+    let queries = [query("COMP2123"), query("INFO1110")];
+    let mut planner = Planner::new(common::config(json!({})));
+
+    planner.update(0, &queries[0], &[allocation(1, "9:00", "1 hr", "1-13")]);
+    // One ends at 10:00 and the other starts at 10:00
+    assert!(planner.clashes(1, &queries, &allocation(2, "10:00", "1 hr", "1-13")).is_empty());
+
+    let clashes = planner.clashes(1, &queries, &allocation(2, "9:30", "1 hr", "1-13"));
+    assert_eq!(clashes, vec![Clash::Allocation {
+        unit_code: "COMP2123".to_owned(),
+        activity: 1,
+        slot: "Tuesday 09:00-10:00".to_owned(),
+        earlier: true,
+    }]);
+}
+
+#[test]
+fn allocations_in_different_weeks_do_not_clash() {
+    let queries = [query("COMP2123"), query("INFO1110")];
+    let mut planner = Planner::new(common::config(json!({
+        "commitments": [{ "name": "Work", "day": 2, "start": "12:00", "end": "14:00", "weeks": "1-6" }]
+    })));
+
+    planner.update(0, &queries[0], &[allocation(1, "9:00", "2 hrs", "1-6")]);
+    assert!(planner.clashes(1, &queries, &allocation(2, "10:00", "1 hr", "7-13")).is_empty());
+    assert!(planner.clashes(1, &queries, &allocation(2, "12:00", "1 hr", "8, 10, 12")).is_empty());
+    assert_eq!(planner.clashes(1, &queries, &allocation(2, "10:00", "1 hr", "6-13")).len(), 1);
+    assert_eq!(planner.clashes(1, &queries, &allocation(2, "13:00", "1 hr", "6-13")).len(), 1);
+}
+
+#[test]
+fn suppresses_clashes_with_commitments_and_earlier_queries() {
+    let queries = [query("COMP2123"), query("INFO1110")];
+    let commitment = json!([{ "name": "Work", "day": 2, "start": "16:00", "end": "18:00" }]);
+    let mut planner = Planner::new(common::config(json!({ "commitments": commitment, "suppress_clashes": true })));
+
+    planner.update(0, &queries[0], &[allocation(1, "9:00", "1 hr", "1-13")]);
+    planner.update(1, &queries[1], &[allocation(2, "9:00", "1 hr", "1-13")]);
+
+    // Only the later query gives way to the earlier one
+    let earlier = planner.clashes(1, &queries, &allocation(2, "9:00", "1 hr", "1-13"));
+    let later = planner.clashes(0, &queries, &allocation(1, "9:00", "1 hr", "1-13"));
+    assert!(planner.suppresses(&earlier));
+    assert!(!later.is_empty() && !planner.suppresses(&later));
+
+    let work = planner.clashes(1, &queries, &allocation(3, "17:00", "1 hr", "1-13"));
+    assert_eq!(work, vec![Clash::Commitment { name: "Work".to_owned(), slot: "Tuesday 16:00-18:00".to_owned() }]);
+    assert!(planner.suppresses(&work));
+
+    planner.reload(common::config(json!({ "commitments": commitment })));
+    assert!(!planner.suppresses(&work));
+}
@@ -0,0 +1,75 @@
+use std::fs;
+use std::path::PathBuf;
+
+use serde_json::json;
+use tokio::time::{self, Duration};
+
+use seatfinder::query::FinderQuery;
+use seatfinder::reload::{ConfigWatcher, QueryDiff};
+
+mod common;
+
+fn tutorial(activity: u64) -> FinderQuery {
+    common::config(json!({ "unit_code": "COMP2123", "day": 2, "activity_type": "Tutorial", "activity": activity }))
+}
+
+fn activities(queries: &[&FinderQuery]) -> Vec<String> {
+    queries.iter().map(|query| query.activity.to_string()).collect()
+}
+
+#[test]
+fn reports_nothing_for_the_same_queries() {
+    let queries = [tutorial(1), tutorial(2)];
+    assert!(QueryDiff::new(&queries, &queries.clone()).is_empty());
+
+    // Moving a query is not a change
+    let reordered = [tutorial(2), tutorial(1)];
+    assert!(QueryDiff::new(&queries, &reordered).is_empty());
+}
+
+#[test]
+fn tells_apart_queries_for_the_same_unit_and_activity_type() {
+    let old = [tutorial(1), tutorial(2)];
+
+    let new = [tutorial(1), tutorial(3)];
+    let diff = QueryDiff::new(&old, &new);
+    assert!(diff.added.is_empty() && diff.removed.is_empty());
+    assert_eq!(diff.changed, [(&old[1], &new[1])]);
+
+    let new = [tutorial(1), tutorial(2), tutorial(3)];
+    let diff = QueryDiff::new(&old, &new);
+    assert_eq!(activities(&diff.added), ["3"]);
+    assert!(diff.removed.is_empty() && diff.changed.is_empty());
+
+    let new = [tutorial(2)];
+    let diff = QueryDiff::new(&old, &new);
+    assert_eq!(activities(&diff.removed), ["1"]);
+    assert!(diff.added.is_empty() && diff.changed.is_empty());
+}
+
+fn config_path(name: &str) -> PathBuf {
+    let directory = std::env::temp_dir().join(format!("seatfinder-{}-{}", name, std::process::id()));
+    let _ = fs::remove_dir_all(&directory);
+    fs::create_dir_all(&directory).unwrap();
+    let path = directory.join("config.json");
+    fs::write(&path, "{}").unwrap();
+    path
+}
+
+#[tokio::test]
+async fn notices_changes_to_the_config_file_only() {
+    let path = config_path("watcher");
+    let mut watcher = ConfigWatcher::new(&path).unwrap();
+
+    fs::write(path.with_file_name("other.json"), "{}").unwrap();
+    assert!(time::timeout(Duration::from_millis(500), watcher.changed()).await.is_err());
+
+    fs::write(&path, r#"{ "headless": true }"#).unwrap();
+    time::timeout(Duration::from_secs(5), watcher.changed()).await.unwrap();
+
+    // Replacing the file, as editors do when saving, counts as a change
+    let replacement = path.with_file_name("config.json.tmp");
+    fs::write(&replacement, "{}").unwrap();
+    fs::rename(&replacement, &path).unwrap();
+    time::timeout(Duration::from_secs(5), watcher.changed()).await.unwrap();
+}
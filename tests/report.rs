@@ -0,0 +1,79 @@
+use chrono::{Duration, Local, TimeZone};
+
+use seatfinder::history::Observation;
+use seatfinder::report::{csv, summarise, ReportFormat, ReportOptions, Trend};
+
+mod common;
+
+/// Observations of tutorial 4 an hour apart with the given seats, starting at 09:00.
+fn hourly(seats: &[i16]) -> Vec<Observation> {
+    let start = Local.with_ymd_and_hms(2024, 8, 6, 9, 0, 0).unwrap();
+    seats
+        .iter()
+        .enumerate()
+        .map(|(hour, &seats)| common::observation(4, seats, start + Duration::hours(hour as i64)))
+        .collect()
+}
+
+fn options(unit_code: Option<&str>, since: Option<u64>) -> ReportOptions {
+    ReportOptions { format: ReportFormat::Table, unit_code: unit_code.map(str::to_owned), since, history: None }
+}
+
+#[test]
+fn summarises_a_single_observation() {
+    let reports = summarise(&hourly(&[2]));
+    assert_eq!(reports.len(), 1);
+
+    let report = &reports[0];
+    assert_eq!(report.observations, 1);
+    assert_eq!(report.first_seen, report.last_seen);
+    assert_eq!(report.openings, 0);
+    assert_eq!(report.average_open_secs, None);
+    assert_eq!(report.peak_hour(), None);
+    assert_eq!(report.current_seats, 2);
+    assert_eq!(report.trend, Trend::Steady);
+}
+
+#[test]
+fn leaves_openings_still_open_out_of_the_average() {
+    let reports = summarise(&hourly(&[0, 2, 0, 0, 3, 1]));
+    let report = &reports[0];
+    assert_eq!(report.openings, 2);
+    // Only the first opening has closed, after an hour
+    assert_eq!(report.average_open_secs, Some(3600));
+    assert_eq!(report.peak_hour(), Some(10));
+    assert_eq!(report.current_seats, 1);
+
+    let reports = summarise(&hourly(&[0, 0, 4]));
+    assert_eq!(reports[0].openings, 1);
+    assert_eq!(reports[0].average_open_secs, None);
+    assert_eq!(reports[0].trend, Trend::Rising);
+}
+
+#[test]
+fn includes_recent_observations_of_the_unit() {
+    let now = Local::now();
+    let recent = common::observation(4, 1, now - Duration::days(6));
+    let old = common::observation(4, 1, now - Duration::days(8));
+
+    assert!(options(None, None).includes(&old));
+    assert!(options(None, Some(7)).includes(&recent));
+    assert!(!options(None, Some(7)).includes(&old));
+
+    assert!(options(Some("comp2123"), Some(7)).includes(&recent));
+    assert!(!options(Some("INFO1110"), None).includes(&recent));
+}
+
+#[test]
+fn escapes_csv_fields() {
+    let observations: Vec<Observation> = hourly(&[0, 1])
+        .into_iter()
+        .map(|observation| Observation { unit_code: "COMP2123, \"advanced\"".to_owned(), ..observation })
+        .collect();
+
+    let csv = csv(&summarise(&observations));
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(lines.len(), 2);
+    assert!(lines[0].starts_with("unit_code,semester,activity_type,activity,"), "{}", lines[0]);
+    assert!(lines[1].starts_with("\"COMP2123, \"\"advanced\"\"\",semester 2,Tutorial,4,2,1,,10,1,rising,"), "{}", lines[1]);
+}
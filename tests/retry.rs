@@ -0,0 +1,102 @@
+use std::cell::Cell;
+use std::io;
+
+use seatfinder::error::{Error, TableError};
+use seatfinder::outcome::FindStep;
+use seatfinder::retry::{RetryConfig, RetryPolicy};
+use tokio::time::Duration;
+
+mod common;
+
+/// A policy that retries straight away, so that tests do not sleep.
+fn immediate(max_attempts: u32) -> RetryPolicy {
+    RetryPolicy { max_attempts, initial_delay: 0, max_delay: 0, jitter: 0.0, ..RetryPolicy::default() }
+}
+
+fn retryable() -> Error {
+    TableError::RowMissingError("Seats".to_owned()).into()
+}
+
+#[test]
+fn grows_delays_up_to_the_maximum() {
+    let policy = RetryPolicy { initial_delay: 500, max_delay: 3000, multiplier: 2.0, jitter: 0.0, ..RetryPolicy::default() };
+    let delays: Vec<u64> = (1..=5).map(|retry| policy.delay(retry).as_millis() as u64).collect();
+    assert_eq!(delays, [500, 1000, 2000, 3000, 3000]);
+}
+
+#[test]
+fn keeps_jitter_within_bounds() {
+    let policy = RetryPolicy { initial_delay: 1000, max_delay: 1000, jitter: 0.25, ..RetryPolicy::default() };
+    for _ in 0..1000 {
+        let delay = policy.delay(1);
+        assert!((Duration::from_millis(750)..=Duration::from_millis(1250)).contains(&delay), "{:?}", delay);
+    }
+
+    // Jitter is clamped so a delay is never negative nor more than doubled
+    let policy = RetryPolicy { jitter: 3.0, ..policy };
+    for _ in 0..1000 {
+        assert!(policy.delay(1) <= Duration::from_millis(2000));
+    }
+}
+
+#[tokio::test]
+async fn counts_the_first_try_as_an_attempt() {
+    let attempts = Cell::new(0);
+    let result: Result<(), Error> = immediate(3)
+        .retry("scraping", || {
+            attempts.set(attempts.get() + 1);
+            async { Err(retryable()) }
+        })
+        .await;
+    assert!(matches!(result, Err(Error::Table(_))));
+    assert_eq!(attempts.get(), 3);
+
+    attempts.set(0);
+    let result = immediate(3)
+        .retry("scraping", || {
+            attempts.set(attempts.get() + 1);
+            let attempt = attempts.get();
+            async move { if attempt < 2 { Err(retryable()) } else { Ok(attempt) } }
+        })
+        .await;
+    assert_eq!(result.unwrap(), 2);
+
+    attempts.set(0);
+    let _ = immediate(1)
+        .retry("scraping", || {
+            attempts.set(attempts.get() + 1);
+            async { Err::<(), _>(retryable()) }
+        })
+        .await;
+    assert_eq!(attempts.get(), 1);
+}
+
+#[tokio::test]
+async fn does_not_retry_errors_that_are_not_retryable() {
+    let attempts = Cell::new(0);
+    let result: Result<(), Error> = immediate(5)
+        .retry("scraping", || {
+            attempts.set(attempts.get() + 1);
+            async { Err(io::Error::other("disk full")) }
+        })
+        .await;
+    assert!(matches!(result, Err(Error::Io(_))));
+    assert_eq!(attempts.get(), 1);
+}
+
+#[test]
+fn picks_the_policy_of_each_step() {
+    let config: RetryConfig = common::config(serde_json::json!({
+        "navigation": { "max_attempts": 1 },
+        "search": { "max_attempts": 2 },
+        "scraping": { "max_attempts": 3 }
+    }));
+
+    assert_eq!(config.policy(FindStep::LocateInteractees).max_attempts, 1);
+    for step in [FindStep::ToggleAdvancedFilter, FindStep::SearchTimetable, FindStep::SelectUnit, FindStep::ResetTimetable] {
+        assert_eq!(config.policy(step).max_attempts, 2, "{:?}", step);
+    }
+    assert_eq!(config.policy(FindStep::SearchQuery).max_attempts, 3);
+    // Fields that are left out keep their defaults
+    assert_eq!(config.search.initial_delay, RetryPolicy::default().initial_delay);
+}
@@ -0,0 +1,113 @@
+use chrono::{DateTime, Local, TimeZone};
+use seatfinder::query::FinderQuery;
+use seatfinder::schedule::{ScheduleConfig, Scheduler};
+use serde_json::json;
+use tokio::time::{Duration, Instant};
+
+mod common;
+
+fn query(activity: u64, interval: Option<u64>) -> FinderQuery {
+    let mut query = json!({ "unit_code": "COMP2123", "day": 2, "activity_type": "Tutorial", "activity": activity });
+    if let Some(interval) = interval {
+        query["interval"] = json!(interval);
+    }
+    common::config(query)
+}
+
+/// A Tuesday.
+fn at(hour: u32, minute: u32) -> DateTime<Local> {
+    Local.with_ymd_and_hms(2024, 7, 30, hour, minute, 0).unwrap()
+}
+
+#[test]
+fn schedules_queries_by_their_intervals() {
+    let queries = [query(1, None), query(2, Some(300))];
+    let mut scheduler = Scheduler::new(common::config(json!({ "interval": 60 })), &queries);
+    let local = at(12, 0);
+
+    let now = Instant::now();
+    assert_eq!(scheduler.due(now, local), vec![0, 1]);
+    assert_eq!(scheduler.next_wake(now, local), now);
+
+    scheduler.completed(&[0, 1], true);
+    let now = Instant::now();
+    assert!(scheduler.due(now, local).is_empty());
+    assert_eq!(scheduler.interval(&queries[0], local), Duration::from_secs(60));
+    assert_eq!(scheduler.interval(&queries[1], local), Duration::from_secs(300));
+
+    let wake = scheduler.next_wake(now, local);
+    assert!(wake > now && wake <= now + Duration::from_secs(60));
+    assert_eq!(scheduler.due(now + Duration::from_secs(61), local), vec![0]);
+    assert_eq!(scheduler.due(now + Duration::from_secs(301), local), vec![0, 1]);
+}
+
+#[test]
+fn uses_window_intervals() {
+    let config = common::config(json!({
+        "interval": 60,
+        "windows": [{ "start": "09:00", "end": "10:00", "days": ["Tue"], "interval": 10 }]
+    }));
+    let queries = [query(1, Some(300))];
+    let scheduler = Scheduler::new(config, &queries);
+
+    assert_eq!(scheduler.interval(&queries[0], at(9, 30)), Duration::from_secs(10));
+    assert_eq!(scheduler.interval(&queries[0], at(10, 0)), Duration::from_secs(300));
+    // Wednesday is not in the window
+    let wednesday = Local.with_ymd_and_hms(2024, 7, 31, 9, 30, 0).unwrap();
+    assert_eq!(scheduler.interval(&queries[0], wednesday), Duration::from_secs(300));
+}
+
+#[test]
+fn defers_during_quiet_hours() {
+    let config = common::config(json!({ "quiet_hours": [{ "start": "23:00", "end": "07:00" }] }));
+    let mut scheduler = Scheduler::new(config, &[query(1, None)]);
+
+    assert_eq!(scheduler.quiet_until(at(12, 0)), None);
+    assert_eq!(scheduler.quiet_until(at(23, 30)), Some(Local.with_ymd_and_hms(2024, 7, 31, 7, 0, 0).unwrap()));
+    assert_eq!(scheduler.quiet_until(at(6, 59)), Some(at(7, 0)));
+    assert_eq!(scheduler.quiet_until(at(7, 0)), None);
+
+    let now = Instant::now();
+    let until = now + Duration::from_secs(3600);
+    scheduler.defer(until);
+    assert!(scheduler.due(now, at(23, 30)).is_empty());
+    assert_eq!(scheduler.next_wake(now, at(23, 30)), until);
+    assert_eq!(scheduler.due(until, at(7, 0)), vec![0]);
+
+    // A reload works quiet hours out again from the new config
+    scheduler.defer(until);
+    scheduler.reload(ScheduleConfig::default(), &[query(1, None)]);
+    assert_eq!(scheduler.due(now, at(23, 30)), vec![0]);
+}
+
+#[test]
+fn backs_off_after_failures() {
+    let config = common::config(json!({ "interval": 60, "backoff": { "multiplier": 2.0, "max_interval": 300 } }));
+    let queries = [query(1, None)];
+    let mut scheduler = Scheduler::new(config, &queries);
+    let local = at(12, 0);
+
+    let intervals: Vec<u64> = (0..4)
+        .map(|_| {
+            scheduler.completed(&[0], false);
+            scheduler.interval(&queries[0], local).as_secs()
+        })
+        .collect();
+    assert_eq!(intervals, vec![120, 240, 300, 300]);
+    assert_eq!(scheduler.failures(), 4);
+
+    scheduler.completed(&[0], true);
+    assert_eq!(scheduler.failures(), 0);
+    assert_eq!(scheduler.interval(&queries[0], local), Duration::from_secs(60));
+}
+
+#[test]
+fn keeps_timers_of_unchanged_queries_on_reload() {
+    let mut scheduler = Scheduler::new(ScheduleConfig::default(), &[query(1, None)]);
+    scheduler.completed(&[0], true);
+
+    scheduler.reload(common::config(json!({ "interval": 120 })), &[query(1, None), query(2, None)]);
+    let now = Instant::now();
+    assert_eq!(scheduler.due(now, at(12, 0)), vec![1]);
+    assert_eq!(scheduler.interval(&query(1, None), at(12, 0)), Duration::from_secs(120));
+}
@@ -0,0 +1,148 @@
+use chrono::Month;
+use seatfinder::allocation::Semester;
+use seatfinder::offering::Offering;
+use seatfinder::session::{Session, SessionPart};
+
+/// Subcodes as listed by the timetable, with the session each is in.
+const CATALOGUE: [(&str, Session); 10] = [
+    ("COMP2123-S2C-ND-CC", Session::Semester(Semester::Two)),
+    ("INFO1110-S1C-ND-CC", Session::Semester(Semester::One)),
+    ("COMP9001-S1C-ND-RE", Session::Semester(Semester::One)),
+    ("MATH1021-S1C-ND-CC", Session::Semester(Semester::One)),
+    ("EDPC5021-S1CIJA-ND-CC", Session::Intensive(Some(Month::January))),
+    ("LAWS6061-S1CIFE-ND-CC", Session::Intensive(Some(Month::February))),
+    ("LAWS6252-S1CIMR-ND-CC", Session::Intensive(Some(Month::March))),
+    ("MKTG6011-S2CIJL-ND-CC", Session::Intensive(Some(Month::July))),
+    ("BUSS6000-S2CIAU-ND-CC", Session::Intensive(Some(Month::August))),
+    ("PUBH5033-S2CINO-ND-CC", Session::Intensive(Some(Month::November))),
+];
+
+/// Summer and winter school subcodes in the format that is assumed for them, which
+/// has not been checked against subcodes listed by the timetable.
+const UNVERIFIED: [(&str, Session); 4] = [
+    ("BIOL1007-S1CSUMM-ND-CC", Session::Summer(Some(SessionPart::Main))),
+    ("ECON1001-S1CSUME-ND-CC", Session::Summer(Some(SessionPart::Early))),
+    ("ECON1002-S2CWINM-ND-CC", Session::Winter(Some(SessionPart::Main))),
+    ("ECON1002-S2CWIN-ND-CC", Session::Winter(None)),
+];
+
+#[test]
+fn parses_catalogue_of_subcodes() {
+    for (subcode, session) in CATALOGUE.iter().chain(UNVERIFIED.iter()) {
+        let offering: Offering = subcode.parse().unwrap();
+
+        assert_eq!(offering.subcode(), *subcode);
+        assert_eq!(offering.unit_code(), &subcode[..8]);
+        assert_eq!(offering.session(), session, "{}", subcode);
+        assert_eq!(offering.attendance(), Some("ND"));
+        assert!(session.matches(offering.session()), "{}", subcode);
+    }
+
+    let offering: Offering = "comp2123-s2c".parse().unwrap();
+    assert_eq!(offering.session_code(), "S2C");
+    assert_eq!(offering.location(), None);
+    assert_eq!(
+        "COMP2123-S3CXY-ND-CC".parse::<Offering>().unwrap().session(),
+        &Session::Other("S3CXY".to_owned())
+    );
+    assert!("COMP2123".parse::<Offering>().is_err());
+}
+
+#[test]
+fn ignores_text_after_listed_subcodes() {
+    let listed: Offering = "COMP2123-S2C-ND-CC  (Semester 2, Camperdown)".parse().unwrap();
+    assert_eq!(listed.subcode(), "COMP2123-S2C-ND-CC");
+    assert_eq!(listed.location(), Some("CC"));
+
+    let pinned: Offering = "comp2123-s2c-nd-cc".parse().unwrap();
+    assert!(pinned.is("COMP2123-S2C-ND-CC  (Semester 2, Camperdown)"));
+    assert!(pinned.is("COMP2123-S2C-ND-CC\n"));
+    assert!(!pinned.is("COMP2123-S2C-ND-RE (Semester 2)"));
+    assert!(!pinned.is("COMP2123-S2C-ND-CCX"));
+}
+
+#[test]
+fn parses_intensive_month_codes() {
+    let months = [
+        ("JA", Month::January),
+        ("FE", Month::February),
+        ("MR", Month::March),
+        ("AP", Month::April),
+        ("MY", Month::May),
+        ("JN", Month::June),
+        ("JL", Month::July),
+        ("AU", Month::August),
+        ("SE", Month::September),
+        ("OC", Month::October),
+        ("NO", Month::November),
+        ("DE", Month::December),
+    ];
+    for (code, month) in months {
+        assert_eq!(Session::from_code(&format!("S1CI{}", code)), Session::Intensive(Some(month)), "{}", code);
+    }
+
+    // Codes that could stand for more than one month are not guessed at
+    for code in ["S1CIMA", "S1CIJU"] {
+        assert_eq!(Session::from_code(code), Session::Other(code.to_owned()));
+    }
+}
+
+#[test]
+fn parses_config_values() {
+    let parse = |value: &str| value.parse::<Session>().unwrap();
+
+    assert_eq!(parse("0"), Session::Any);
+    assert_eq!(parse("2"), Session::Semester(Semester::Two));
+    assert_eq!(parse("Semester 1"), Session::Semester(Semester::One));
+    assert_eq!(parse("S1CIJA"), Session::Intensive(Some(Month::January)));
+    assert_eq!(parse("intensive"), Session::Intensive(None));
+    assert_eq!(parse("Intensive July"), Session::Intensive(Some(Month::July)));
+    assert_eq!(parse("intensive sep"), Session::Intensive(Some(Month::September)));
+    assert_eq!(parse("summer"), Session::Summer(None));
+    assert_eq!(parse("Summer Late"), Session::Summer(Some(SessionPart::Late)));
+    assert_eq!(parse("winter main"), Session::Winter(Some(SessionPart::Main)));
+    assert_eq!(parse("S4C"), Session::Other("S4C".to_owned()));
+    assert!("3".parse::<Session>().is_err());
+    assert!("sumer".parse::<Session>().is_err());
+    assert!("intensive smarch".parse::<Session>().is_err());
+
+    for session in [Session::Semester(Semester::Two), Session::Intensive(Some(Month::June)), Session::Winter(None)] {
+        assert_eq!(session.to_string().parse::<Session>().unwrap(), session);
+    }
+
+    let session: Session = serde_json::from_value(serde_json::json!("summer main")).unwrap();
+    assert_eq!(session, Session::Summer(Some(SessionPart::Main)));
+    assert_eq!(serde_json::to_value(Session::Semester(Semester::One)).unwrap(), serde_json::json!(1));
+    assert_eq!(serde_json::to_value(&session).unwrap(), serde_json::json!("summer main"));
+}
+
+#[test]
+fn matches_offered_sessions() {
+    let summer = Session::Summer(Some(SessionPart::Main));
+    let january = Session::Intensive(Some(Month::January));
+
+    assert!(Session::Any.matches(&summer));
+    assert!(Session::Summer(None).matches(&summer));
+    assert!(!Session::Summer(Some(SessionPart::Early)).matches(&summer));
+    assert!(!Session::Winter(None).matches(&summer));
+    assert!(Session::Intensive(None).matches(&january));
+    assert!(!Session::Intensive(Some(Month::July)).matches(&january));
+    // Semester 1 only includes the intensive sessions coded under it when matching offerings
+    assert!(!Session::Semester(Semester::One).matches(&january));
+    assert!(!Session::Semester(Semester::Two).matches(&Session::Other("S3C".to_owned())));
+    assert!(Session::Other("s3c".to_owned()).matches(&Session::Other("S3C".to_owned())));
+}
+
+#[test]
+fn takes_in_sessions_coded_under_a_semester() {
+    let offering = |subcode: &str| subcode.parse::<Offering>().unwrap();
+    let one = Session::Semester(Semester::One);
+
+    assert!(offering("INFO1110-S1C-ND-CC").is_in(&one));
+    assert!(offering("EDPC5021-S1CIJA-ND-CC").is_in(&one));
+    assert!(!offering("MKTG6011-S2CIJL-ND-CC").is_in(&one));
+    assert!(!offering("COMP2123-S2C-ND-CC").is_in(&one));
+    assert!(!offering("EDPC5021-S1CIJA-ND-CC").is_in(&Session::Intensive(Some(Month::July))));
+    assert!(offering("EDPC5021-S1CIJA-ND-CC").is_in(&Session::Intensive(None)));
+    assert!(offering("COMP2123-S3CXY-ND-CC").is_in(&Session::Any));
+}
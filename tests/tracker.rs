@@ -0,0 +1,88 @@
+use std::time::{Duration, Instant};
+
+use seatfinder::query::FinderQuery;
+use seatfinder::tracker::{SeatTracker, Transition};
+use serde_json::json;
+
+mod common;
+
+fn query() -> FinderQuery {
+    common::config(json!({ "unit_code": "COMP2123", "day": 2, "activity_type": "Tutorial" }))
+}
+
+/// The transitions of an allocation when it has each number of seats, `seconds` after `start`.
+fn observe(tracker: &mut SeatTracker, query: &FinderQuery, start: Instant, steps: &[(u64, i16)]) -> Vec<Option<Transition>> {
+    steps
+        .iter()
+        .map(|&(seconds, seats)| {
+            let allocation = common::allocation().seats(seats).build();
+            tracker.observe_at(query, &allocation, start + Duration::from_secs(seconds))
+        })
+        .collect()
+}
+
+#[test]
+fn notifies_when_seats_change() {
+    let mut tracker = SeatTracker::new(common::config(json!({ "cooldown": 0, "threshold": 5, "reminder": 60 })));
+    let steps = [(0, 0), (1, 2), (2, 3), (3, 5), (4, 6), (64, 6), (65, 0), (66, 0), (67, 1)];
+
+    assert_eq!(observe(&mut tracker, &query(), Instant::now(), &steps), vec![
+        None,
+        Some(Transition::Available { seats: 2 }),
+        None,
+        Some(Transition::Increased { from: 2, to: 5 }),
+        None,
+        Some(Transition::Reminder { seats: 6 }),
+        Some(Transition::SoldOut),
+        None,
+        Some(Transition::Available { seats: 1 }),
+    ]);
+}
+
+#[test]
+fn counts_fewer_than_min_seats_as_none() {
+    let mut tracker = SeatTracker::new(common::config(json!({ "cooldown": 0 })));
+    let query = common::config(json!({ "unit_code": "COMP2123", "day": 2, "activity_type": "Tutorial", "min_seats": 2 }));
+    let steps = [(0, 1), (1, 2), (2, 1), (3, 3)];
+
+    assert_eq!(observe(&mut tracker, &query, Instant::now(), &steps), vec![
+        None,
+        Some(Transition::Available { seats: 2 }),
+        Some(Transition::SoldOut),
+        Some(Transition::Available { seats: 3 }),
+    ]);
+}
+
+#[test]
+fn waits_for_the_cooldown() {
+    let mut tracker = SeatTracker::new(common::config(json!({ "cooldown": 300, "reminder": 60 })));
+    // Seats that sell out and come back during the cooldown are not notified about,
+    // and a change that lasts past it is
+    let steps = [(0, 2), (10, 0), (20, 2), (120, 2), (200, 0), (299, 0), (300, 0), (310, 4)];
+
+    assert_eq!(observe(&mut tracker, &query(), Instant::now(), &steps), vec![
+        Some(Transition::Available { seats: 2 }),
+        None,
+        None,
+        None,
+        None,
+        None,
+        Some(Transition::SoldOut),
+        None,
+    ]);
+}
+
+#[test]
+fn tracks_each_activity_separately() {
+    let mut tracker = SeatTracker::new(common::config(json!({})));
+    let query = query();
+    let first = common::allocation().activity(1).seats(2).build();
+    let second = common::allocation().activity(2).seats(3).build();
+
+    assert_eq!(tracker.observe(&query, &first), Some(Transition::Available { seats: 2 }));
+    assert_eq!(tracker.observe(&query, &second), Some(Transition::Available { seats: 3 }));
+    assert_eq!(tracker.observe(&query, &first), None);
+
+    tracker.forget(&query, &first);
+    assert_eq!(tracker.observe(&query, &first), Some(Transition::Available { seats: 2 }));
+}
@@ -0,0 +1,217 @@
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::mpsc::{self, Receiver};
+use std::thread;
+
+use serde_json::{json, Value};
+
+use seatfinder::config::Config;
+use seatfinder::notifier::{render, Notification, Notifier, NotifierConfig, WebhookNotifier};
+use seatfinder::query::FinderQuery;
+use seatfinder::tracker::Transition;
+
+mod common;
+
+/// A request captured by [`receiver`].
+struct Request {
+    headers: Vec<(String, String)>,
+    body: Value,
+}
+
+impl Request {
+    fn header(&self, name: &str) -> Option<&str> {
+        self.headers
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, value)| value.as_str())
+    }
+}
+
+/// Starts an HTTP server on a free port that answers the `n`th request with
+/// `statuses[n]` and sends every request it receives through the channel.
+fn receiver(statuses: Vec<u16>) -> (String, Receiver<Request>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let url = format!("http://{}/hook", listener.local_addr().unwrap());
+    let (sender, requests) = mpsc::channel();
+
+    thread::spawn(move || {
+        for status in statuses {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+            let mut headers = Vec::new();
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            loop {
+                line.clear();
+                reader.read_line(&mut line).unwrap();
+                match line.trim_end().split_once(": ") {
+                    Some((key, value)) => headers.push((key.to_owned(), value.to_owned())),
+                    None => break,
+                }
+            }
+
+            let length = headers
+                .iter()
+                .find(|(key, _)| key.eq_ignore_ascii_case("content-length"))
+                .map(|(_, value)| value.parse().unwrap())
+                .unwrap_or(0);
+            let mut body = vec![0; length];
+            reader.read_exact(&mut body).unwrap();
+
+            let response = format!("HTTP/1.1 {} Status\r\ncontent-length: 0\r\nconnection: close\r\n\r\n", status);
+            stream.write_all(response.as_bytes()).unwrap();
+            sender.send(Request { headers, body: serde_json::from_slice(&body).unwrap() }).unwrap();
+        }
+    });
+
+    (url, requests)
+}
+
+fn notification() -> Notification {
+    let query: FinderQuery = common::config(json!({
+        "unit_code": "COMP2123",
+        "semester": 2,
+        "day": "Tue",
+        "activity_type": "Tutorial",
+        "activity": 4,
+        "start_after": "11:00"
+    }));
+
+    let allocation = common::allocation().activity(4).seats(3).build();
+
+    Notification { query, allocation, transition: Transition::Available { seats: 3 }, better_than: None, clashes: Vec::new() }
+}
+
+#[tokio::test]
+async fn posts_allocation_and_query() {
+    let (url, requests) = receiver(vec![200]);
+    let notifier = WebhookNotifier::new(common::config(json!({ "url": url })));
+
+    notifier.notify(&notification()).await.unwrap();
+
+    let request = requests.recv().unwrap();
+    assert_eq!(request.header("content-type"), Some("application/json"));
+    assert_eq!(request.body["event"], "available");
+    assert_eq!(request.body["unit_code"], "COMP2123");
+    assert_eq!(request.body["title"], "Seats found for COMP2123");
+    assert_eq!(request.body["allocation"], json!({
+        "activity_type": "Tutorial",
+        "group": "TUT",
+        "activity": 4,
+        "description": "Tutorial",
+        "day": "Tuesday",
+        "time": "12:00",
+        "semester": 2,
+        "campus": "Camperdown/Darlington, Sydney",
+        "location": "Carslaw 173",
+        "duration": "1 hr",
+        "weeks": "2-13",
+        "seats": 3
+    }));
+    assert_eq!(request.body["query"], json!({
+        "unit_code": "COMP2123",
+        "semester": 2,
+        "day": "Tuesday",
+        "activity_type": "Tutorial",
+        "activity": 4,
+        "start_after": "11:00"
+    }));
+}
+
+#[tokio::test]
+async fn sends_headers_and_templated_body() {
+    let (url, requests) = receiver(vec![204]);
+    let notifier = WebhookNotifier::new(common::config(json!({
+        "url": url,
+        "headers": { "Authorization": "Bearer secret", "X-Source": "seatfinder" },
+        "body": {
+            "text": "{unit_code} {activity_type} {activity} on {day} at {time}: {seats} seats ({event})",
+            "fields": [{ "where": "{location}, {campus}" }],
+            "urgent": true
+        }
+    })));
+
+    notifier.notify(&notification()).await.unwrap();
+
+    let request = requests.recv().unwrap();
+    assert_eq!(request.header("authorization"), Some("Bearer secret"));
+    assert_eq!(request.header("x-source"), Some("seatfinder"));
+    assert_eq!(request.body, json!({
+        "text": "COMP2123 Tutorial 4 on Tuesday at 12:00: 3 seats (available)",
+        "fields": [{ "where": "Carslaw 173, Camperdown/Darlington, Sydney" }],
+        "urgent": true
+    }));
+}
+
+#[test]
+fn renders_values_containing_braces_as_they_are() {
+    let variables = [("location", "Room {seats}"), ("seats", "3"), ("description", "{{x}}")]
+        .map(|(key, value)| (key.to_owned(), value.to_owned()))
+        .into();
+    let template = json!({ "text": "{location}: {seats} seats, {description} {unknown} {}" });
+    assert_eq!(render(&template, &variables), json!({ "text": "Room {seats}: 3 seats, {{x}} {unknown} {}" }));
+}
+
+#[tokio::test]
+async fn retries_server_errors() {
+    let (url, requests) = receiver(vec![503, 500, 200]);
+    let notifier = WebhookNotifier::new(common::config(json!({
+        "url": url,
+        "retry": { "max_attempts": 3, "initial_delay": 1, "jitter": 0.0 }
+    })));
+
+    notifier.notify(&notification()).await.unwrap();
+    assert_eq!(requests.iter().take(3).count(), 3);
+}
+
+#[tokio::test]
+async fn gives_up_after_max_attempts() {
+    let (url, requests) = receiver(vec![502, 502]);
+    let notifier = WebhookNotifier::new(common::config(json!({
+        "url": url,
+        "retry": { "max_attempts": 2, "initial_delay": 1, "jitter": 0.0 }
+    })));
+
+    let error = notifier.notify(&notification()).await.unwrap_err();
+    assert_eq!(error.to_string(), "webhook responded with status 502");
+    assert_eq!(requests.iter().take(2).count(), 2);
+}
+
+#[tokio::test]
+async fn does_not_retry_client_errors() {
+    let (url, requests) = receiver(vec![404]);
+    let notifier = WebhookNotifier::new(common::config(json!({
+        "url": url,
+        "retry": { "max_attempts": 3, "initial_delay": 1 }
+    })));
+
+    let error = notifier.notify(&notification()).await.unwrap_err();
+    assert_eq!(error.to_string(), "webhook responded with status 404");
+    assert_eq!(requests.iter().count(), 1);
+}
+
+#[test]
+fn parses_webhook_notifiers() {
+    let query = r#"{ "unit_code": "COMP2123", "day": 1, "activity_type": "Lecture", "activity": 1 }"#;
+
+    let config: Config = format!(
+        r#"{{ "notifiers": {{ "chat": {{ "type": "webhook", "url": "http://localhost/hook", "headers": {{ "X-Token": "abc" }} }} }}, "query": {} }}"#,
+        query
+    )
+    .parse()
+    .unwrap();
+    let Some(NotifierConfig::Webhook(webhook)) = config.finder.notifiers.get("chat") else {
+        panic!("expected a webhook notifier");
+    };
+    assert_eq!(webhook.headers["x-token"], "abc");
+    assert_eq!(config.finder.notify, vec!["chat"]);
+
+    let error = format!(
+        r#"{{ "notifiers": {{ "chat": {{ "type": "webhook", "url": "http://localhost/hook", "headers": {{ "X Token": "abc" }} }} }}, "query": {} }}"#,
+        query
+    )
+    .parse::<Config>()
+    .unwrap_err();
+    assert!(error.to_string().contains("invalid header name \"X Token\""), "{}", error);
+}